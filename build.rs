@@ -0,0 +1,51 @@
+//! Generates a test for every fixture in `fixtures/`, so adding an example is
+//! a matter of dropping in a file.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    println!("cargo::rerun-if-changed={}", root.display());
+
+    let mut fixtures = Vec::new();
+    if let Ok(days) = fs::read_dir(&root) {
+        for day in days.flatten() {
+            let dir_name = day.file_name().to_string_lossy().into_owned();
+            let Some(number) = dir_name
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            for file in fs::read_dir(day.path()).into_iter().flatten().flatten() {
+                let path = file.path();
+                if path.extension().is_some_and(|e| e == "txt") {
+                    let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                    fixtures.push((number, name, path));
+                }
+            }
+        }
+    }
+    fixtures.sort();
+
+    let mut tests = String::new();
+    for (day, name, path) in fixtures {
+        let ident: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        writeln!(
+            tests,
+            "#[test]\nfn day{}_{}() {{\n    check({}, {:?}, include_str!({:?}));\n}}\n",
+            day,
+            ident.to_lowercase(),
+            day,
+            name,
+            path.display().to_string()
+        )
+        .unwrap();
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("fixture_tests.rs");
+    fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,270 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Write},
+    ops::RangeInclusive,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::render::{Frame, Render};
+
+/// Puzzle state that advances one step at a time.
+pub trait Simulation: Render {
+    /// Advances by one step, returning `false` once there's nothing left to do.
+    fn step(&mut self) -> bool;
+}
+
+/// The most frames kept for stepping backwards.
+const HISTORY: usize = 1000;
+
+/// The frame rates the player accepts. Slower than this and the wait between
+/// frames is too long to be a [`Duration`].
+pub const FPS: RangeInclusive<f64> = 0.01..=1000.0;
+
+/// Steps through a [`Simulation`], remembering recent frames so it can also
+/// step backwards.
+pub struct Player<'a> {
+    simulation: Box<dyn Simulation + 'a>,
+    /// Frames from `first` up to the latest one simulated.
+    history: VecDeque<Frame>,
+    first: usize,
+    current: usize,
+    finished: bool,
+}
+
+impl<'a> Player<'a> {
+    pub fn new(simulation: Box<dyn Simulation + 'a>) -> Self {
+        let frame = simulation.render();
+        Self {
+            simulation,
+            history: VecDeque::from([frame]),
+            first: 0,
+            current: 0,
+            finished: false,
+        }
+    }
+
+    /// The index of the frame being shown, starting from 0.
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.history[self.current - self.first]
+    }
+
+    /// Whether the simulation has ended and the last frame is being shown.
+    pub fn at_end(&self) -> bool {
+        self.finished && self.current == self.first + self.history.len() - 1
+    }
+
+    /// Moves to the next frame, simulating it if needed. Returns whether
+    /// there was one.
+    pub fn forward(&mut self) -> bool {
+        if self.current + 1 < self.first + self.history.len() {
+            self.current += 1;
+            return true;
+        }
+        if self.finished || !self.simulation.step() {
+            self.finished = true;
+            return false;
+        }
+
+        self.history.push_back(self.simulation.render());
+        if self.history.len() > HISTORY {
+            self.history.pop_front();
+            self.first += 1;
+        }
+        self.current += 1;
+        true
+    }
+
+    /// Moves to the previous frame, if it's still remembered.
+    pub fn back(&mut self) -> bool {
+        if self.current > self.first {
+            self.current -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves as close to frame `index` as possible.
+    pub fn seek(&mut self, index: usize) {
+        while self.current < index && self.forward() {}
+        while self.current > index && self.back() {}
+    }
+}
+
+/// A control typed into the player, one per line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Control {
+    TogglePause,
+    Forward,
+    Back,
+    Seek(usize),
+    Fps(f64),
+    Quit,
+}
+
+impl Control {
+    fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let control = match words.next().unwrap_or("") {
+            "" | "p" => Control::TogglePause,
+            "n" => Control::Forward,
+            "b" => Control::Back,
+            "g" => Control::Seek(words.next()?.parse().ok()?),
+            "f" => Control::Fps(words.next()?.parse().ok().filter(|fps| FPS.contains(fps))?),
+            "q" => Control::Quit,
+            _ => return None,
+        };
+        Some(control)
+    }
+}
+
+fn draw(player: &Player, paused: bool, fps: f64) -> io::Result<()> {
+    clearscreen::clear().map_err(io::Error::other)?;
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", player.frame().to_ansi())?;
+    writeln!(
+        stdout,
+        "frame {}{} ({}, {} fps)",
+        player.index(),
+        if player.at_end() { ", end" } else { "" },
+        if paused { "paused" } else { "playing" },
+        fps
+    )?;
+    writeln!(
+        stdout,
+        "[enter] pause/play  [n] next  [b] back  [g N] go to frame  [f N] fps  [q] quit"
+    )?;
+    stdout.flush()
+}
+
+/// Plays `simulation` in the terminal at `fps` frames per second, taking
+/// controls from stdin until it's told to quit or stdin closes.
+///
+/// # Panics
+///
+/// If `fps` isn't in [`FPS`].
+pub fn play(simulation: Box<dyn Simulation + '_>, fps: f64) -> io::Result<()> {
+    assert!(FPS.contains(&fps), "{} fps is out of range", fps);
+    let (sender, controls) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if let Some(control) = Control::parse(&line) {
+                if sender.send(control).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut player = Player::new(simulation);
+    let mut paused = false;
+    let mut fps = fps;
+
+    loop {
+        draw(&player, paused, fps)?;
+
+        let control = if paused || player.at_end() {
+            controls.recv().ok()
+        } else {
+            match controls.recv_timeout(Duration::from_secs_f64(1.0 / fps)) {
+                Ok(control) => Some(control),
+                Err(RecvTimeoutError::Timeout) => {
+                    player.forward();
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            }
+        };
+
+        match control {
+            Some(Control::TogglePause) => paused = !paused,
+            Some(Control::Forward) => {
+                paused = true;
+                player.forward();
+            }
+            Some(Control::Back) => {
+                paused = true;
+                player.back();
+            }
+            Some(Control::Seek(index)) => {
+                paused = true;
+                player.seek(index);
+            }
+            Some(Control::Fps(new_fps)) => fps = new_fps,
+            Some(Control::Quit) | None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::*;
+    use crate::{grid::Grid, render::Cell};
+
+    struct Countdown(u32);
+
+    impl Render for Countdown {
+        fn render(&self) -> Frame {
+            let digit = char::from_digit(self.0, 10).unwrap();
+            Frame::new(Grid::new(IVec2::ONE, Cell::new(digit)))
+        }
+    }
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> bool {
+            if self.0 == 0 {
+                return false;
+            }
+            self.0 -= 1;
+            true
+        }
+    }
+
+    #[test]
+    fn test_player() {
+        let mut player = Player::new(Box::new(Countdown(3)));
+        assert_eq!(player.frame().to_string(), "3");
+        assert!(!player.back());
+
+        assert!(player.forward());
+        assert!(player.forward());
+        assert_eq!(player.frame().to_string(), "1");
+        assert!(player.back());
+        assert_eq!(
+            (player.index(), player.frame().to_string()),
+            (1, "2".into())
+        );
+
+        player.seek(10);
+        assert_eq!(
+            (player.index(), player.frame().to_string()),
+            (3, "0".into())
+        );
+        assert!(player.at_end());
+        assert!(!player.forward());
+
+        player.seek(0);
+        assert_eq!(player.frame().to_string(), "3");
+    }
+
+    #[test]
+    fn test_control_parse() {
+        assert_eq!(Control::parse(""), Some(Control::TogglePause));
+        assert_eq!(Control::parse("g 42"), Some(Control::Seek(42)));
+        assert_eq!(Control::parse("f 2.5"), Some(Control::Fps(2.5)));
+        assert_eq!(Control::parse("f 0"), None);
+        assert_eq!(Control::parse("f 1e-300"), None);
+        assert_eq!(Control::parse("f NaN"), None);
+        assert_eq!(Control::parse("g"), None);
+        assert_eq!(Control::parse("x"), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// The answer to one part of a puzzle.
+///
+/// Integers compare by value regardless of signedness, so `Signed(5)` equals
+/// `Unsigned(5)`, and text compares as it's displayed, so a single line of
+/// `Lines` equals the same `Text`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+    /// Output rendered over several lines, such as letters drawn in a grid.
+    Lines(Vec<String>),
+}
+
+impl Answer {
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Unsigned(n) => Some(*n as i128),
+            Answer::Signed(n) => Some(*n as i128),
+            Answer::Text(_) | Answer::Lines(_) => None,
+        }
+    }
+
+    /// The answer as displayed, if it's text.
+    fn as_text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Answer::Text(text) => Some(Cow::Borrowed(text)),
+            Answer::Lines(lines) => Some(Cow::Owned(lines.join("\n"))),
+            Answer::Unsigned(_) | Answer::Signed(_) => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i128().and_then(|n| n.try_into().ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|n| n.try_into().ok())
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_text(), other.as_text()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.as_i128().is_some() && self.as_i128() == other.as_i128(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.as_text() {
+            Some(text) => text.hash(state),
+            None => self.as_i128().hash(state),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+/// Parses integers as numbers, anything spanning several lines as
+/// [`Answer::Lines`] and everything else as [`Answer::Text`].
+///
+/// Parsing an answer's `Display` output gives back an equal answer, except
+/// for text that reads as an integer, such as `Text("5")` or a single line
+/// `Lines(["-3"])`, which comes back as a number. Numbers and text never
+/// compare equal, so those don't round-trip.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if let Ok(n) = s.parse() {
+            Answer::Unsigned(n)
+        } else if let Ok(n) = s.parse() {
+            Answer::Signed(n)
+        } else if s.contains('\n') {
+            Answer::Lines(s.lines().map(String::from).collect())
+        } else {
+            Answer::Text(s.to_string())
+        })
+    }
+}
+
+macro_rules! impl_int_conversions {
+    ($variant:ident($inner:ty): $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $inner)
+                }
+            }
+
+            impl TryFrom<Answer> for $t {
+                type Error = Answer;
+
+                fn try_from(answer: Answer) -> Result<Self, Self::Error> {
+                    answer
+                        .as_i128()
+                        .and_then(|n| n.try_into().ok())
+                        .ok_or(answer)
+                }
+            }
+
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, other: &$t) -> bool {
+                    self.as_i128() == Some(*other as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_int_conversions!(Unsigned(u64): u32, u64, usize);
+impl_int_conversions!(Signed(i64): i32, i64);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Self {
+        Answer::Lines(lines)
+    }
+}
+
+/// Compares against the answer's `Display` output, which is how answers are
+/// written down.
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_answer_equality() {
+        assert_eq!(Answer::Signed(5), Answer::Unsigned(5));
+        assert_ne!(Answer::Signed(-5), Answer::Unsigned(5));
+        assert_ne!(Answer::Text("5".into()), Answer::Unsigned(5));
+        assert_eq!(Answer::from(31u32), 31usize);
+        assert_eq!(Answer::from(-2i32), "-2");
+        assert_eq!(
+            Answer::from(vec!["#.#".to_string(), ".#.".to_string()]),
+            "#.#\n.#."
+        );
+
+        assert_eq!(Answer::from(vec!["#.#".to_string()]), Answer::from("#.#"));
+        assert_eq!(Answer::Lines(Vec::new()), Answer::from(""));
+        assert_ne!(Answer::from(vec!["1".to_string()]), Answer::Unsigned(1));
+
+        let set: HashSet<_> = [Answer::Signed(7), Answer::Unsigned(7)].into();
+        assert_eq!(set.len(), 1);
+        let set: HashSet<_> = [Answer::Lines(vec!["#".into()]), Answer::from("#")].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_answer_conversions() {
+        assert_eq!(u64::try_from(Answer::Signed(3)), Ok(3));
+        assert_eq!(u64::try_from(Answer::Signed(-3)), Err(Answer::Signed(-3)));
+        assert_eq!(i32::try_from(Answer::Unsigned(u64::MAX)).ok(), None);
+        assert_eq!(Answer::Text("x".into()).as_i64(), None);
+
+        for answer in [
+            Answer::Unsigned(1985268524462),
+            Answer::Signed(-17),
+            Answer::Text("1,2,3".into()),
+            Answer::Lines(vec!["##".into(), "..".into()]),
+            Answer::Lines(vec!["##".into()]),
+            Answer::Lines(Vec::new()),
+        ] {
+            assert_eq!(answer.to_string().parse(), Ok(answer));
+        }
+        for answer in [Answer::from("5"), Answer::Lines(vec!["-3".into()])] {
+            assert_ne!(answer.to_string().parse(), Ok(answer));
+        }
+    }
+}
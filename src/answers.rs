@@ -0,0 +1,146 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use nom::{
+    bytes::complete::is_not,
+    character::complete::{space1, u32},
+    combinator::map,
+    sequence::tuple,
+};
+
+use crate::{
+    answer::Answer,
+    parse::{finish, IResult, ParseError},
+};
+
+/// Identifies one answer: a part of a day solved against a named input.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+}
+
+impl Key {
+    pub fn new(day: u32, part: u32, input: impl Into<String>) -> Self {
+        Self {
+            day,
+            part,
+            input: input.into(),
+        }
+    }
+}
+
+/// Known-good answers, stored one per line as `day part input answer`.
+/// Lines starting with `#` are comments, and the lines of a multi-line answer
+/// are joined with a literal `\n`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<Key, Answer>,
+}
+
+impl Answers {
+    pub fn get(&self, key: &Key) -> Option<&Answer> {
+        self.answers.get(key)
+    }
+
+    /// Records `answer` for `key`, returning the answer it replaced.
+    pub fn insert(&mut self, key: Key, answer: impl Into<Answer>) -> Option<Answer> {
+        self.answers.insert(key, answer.into())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Answer)> {
+        self.answers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+fn parse_entry(input: &str) -> IResult<&str, (Key, Answer)> {
+    map(
+        tuple((
+            u32,
+            space1,
+            u32,
+            space1,
+            is_not(" \t\r\n"),
+            space1,
+            is_not("\r\n"),
+        )),
+        |(day, _, part, _, name, _, answer): (_, _, _, _, &str, _, &str)| {
+            let answer = answer.trim_end().replace("\\n", "\n");
+            let Ok(answer) = answer.parse();
+            (Key::new(day, part, name), answer)
+        },
+    )(input)
+}
+
+impl FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut answers = BTreeMap::new();
+
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, answer) =
+                finish(line, parse_entry).map_err(|e| ParseError { line: i + 1, ..e })?;
+            answers.insert(key, answer);
+        }
+
+        Ok(Self { answers })
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day part input answer")?;
+        for (key, answer) in self.iter() {
+            let answer = answer.to_string().replace('\n', "\\n");
+            writeln!(f, "{} {} {} {}", key.day, key.part, key.input, answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_round_trip() {
+        let input = "# day part input answer
+1 1 real 2196996
+
+1 2 real 23655822
+14 2 example none yet
+16 1 real ##.\\n.##
+";
+        let answers: Answers = input.parse().unwrap();
+        assert_eq!(answers.len(), 4);
+        assert_eq!(
+            answers.get(&Key::new(1, 2, "real")),
+            Some(&Answer::Unsigned(23655822))
+        );
+        assert_eq!(
+            answers.get(&Key::new(14, 2, "example")),
+            Some(&Answer::from("none yet"))
+        );
+        assert_eq!(
+            answers.get(&Key::new(16, 1, "real")),
+            Some(&Answer::from(vec!["##.".to_string(), ".##".to_string()]))
+        );
+        assert_eq!(answers.get(&Key::new(1, 2, "example")), None);
+        assert_eq!(answers.to_string().parse(), Ok(answers));
+
+        let error = "1 1 real 5\n2 x real 6\n".parse::<Answers>().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2024::runner::{self, Solver};
+
+use crate::{table::Table, Input};
+
+pub struct Options<'a> {
+    pub year: u32,
+    pub day: Option<u32>,
+    pub iterations: u32,
+    pub budget: Duration,
+    pub baseline: Option<&'a Path>,
+    pub save_baseline: Option<&'a Path>,
+    /// How much slower than the baseline, in percent, counts as a regression.
+    pub threshold: f64,
+}
+
+/// Median timings for one part of a day.
+#[derive(Debug, Clone, Copy)]
+struct Timing {
+    parse: Duration,
+    solve: Duration,
+}
+
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+    durations[durations.len() / 2]
+}
+
+/// Runs `solver` until it has run `iterations` times or spent `budget`,
+/// whichever comes first, always running at least once.
+fn measure(
+    solver: &Solver,
+    input: &str,
+    iterations: u32,
+    budget: Duration,
+) -> Result<(Timing, usize), String> {
+    let start = Instant::now();
+    let mut parse = Vec::new();
+    let mut solve = Vec::new();
+
+    while parse.len() < iterations.max(1) as usize && (parse.is_empty() || start.elapsed() < budget)
+    {
+        let run = solver.run(input).map_err(|e| e.to_string())?;
+        parse.push(run.parse_time);
+        solve.push(run.solve_time);
+    }
+
+    let runs = parse.len();
+    Ok((
+        Timing {
+            parse: median(parse),
+            solve: median(solve),
+        },
+        runs,
+    ))
+}
+
+/// Baselines are stored one part per line as `day part parse_ns solve_ns`.
+fn load_baseline(path: &Path) -> Result<BTreeMap<(u32, u32), Timing>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let fields: Vec<u64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .ok()
+                .filter(|fields: &Vec<u64>| fields.len() == 4)
+                .ok_or_else(|| {
+                    format!(
+                        "{} line {}: expected `day part parse_ns solve_ns`",
+                        path.display(),
+                        i + 1
+                    )
+                })?;
+            Ok((
+                (fields[0] as u32, fields[1] as u32),
+                Timing {
+                    parse: Duration::from_nanos(fields[2]),
+                    solve: Duration::from_nanos(fields[3]),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn save_baseline(path: &Path, timings: &BTreeMap<(u32, u32), Timing>) -> Result<(), String> {
+    let mut contents = String::from("# day part parse_ns solve_ns\n");
+    for ((day, part), timing) in timings {
+        contents += &format!(
+            "{} {} {} {}\n",
+            day,
+            part,
+            timing.parse.as_nanos(),
+            timing.solve.as_nanos()
+        );
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// The change from `before` to `after` as a signed percentage.
+fn change(before: Duration, after: Duration) -> f64 {
+    (after.as_secs_f64() / before.as_secs_f64().max(f64::EPSILON) - 1.0) * 100.0
+}
+
+/// Benchmarks every solution against the real inputs and prints a summary,
+/// compared against a saved baseline if one is given. Returns whether every
+/// solution ran successfully.
+pub fn bench(options: &Options) -> bool {
+    if cfg!(debug_assertions) {
+        eprintln!("warning: benchmarking a debug build; use `cargo run --release`");
+    }
+
+    let baseline = match options.baseline.map(load_baseline).transpose() {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    let mut timings = BTreeMap::new();
+    let mut table = Table::new(if baseline.is_some() {
+        vec![
+            "Day", "Part", "Runs", "Parse", "Solve", "Baseline", "Change",
+        ]
+    } else {
+        vec!["Day", "Part", "Runs", "Parse", "Solve"]
+    });
+
+    let solvers =
+        runner::solvers().filter(|solver| options.day.is_none_or(|day| solver.day == day));
+    for solver in solvers {
+        let measured = Input::real(options.year)
+            .read(solver.day)
+            .and_then(|input| measure(&solver, &input, options.iterations, options.budget));
+
+        let (timing, runs) = match measured {
+            Ok(measured) => measured,
+            Err(e) => {
+                eprintln!("Day {} part {}: {}", solver.day, solver.part, e);
+                ok = false;
+                continue;
+            }
+        };
+        timings.insert((solver.day, solver.part), timing);
+
+        let mut row = vec![
+            solver.day.to_string(),
+            solver.part.to_string(),
+            runs.to_string(),
+            format!("{:.2?}", timing.parse),
+            format!("{:.2?}", timing.solve),
+        ];
+        if let Some(baseline) = &baseline {
+            match baseline.get(&(solver.day, solver.part)) {
+                Some(before) => {
+                    let total = |t: &Timing| t.parse + t.solve;
+                    let change = change(total(before), total(&timing));
+                    let flag = if change > options.threshold {
+                        " REGRESSION"
+                    } else if change < -options.threshold {
+                        " faster"
+                    } else {
+                        ""
+                    };
+                    row.push(format!("{:.2?}", total(before)));
+                    row.push(format!("{:+.1}%{}", change, flag));
+                }
+                None => row.extend(["-".to_string(), "new".to_string()]),
+            }
+        }
+        table.row(row);
+    }
+
+    print!("{}", table);
+    let total: Duration = timings.values().map(|t| t.parse + t.solve).sum();
+    println!("\nTotal: {:.2?}", total);
+
+    if let Some(path) = options.save_baseline {
+        match save_baseline(path, &timings) {
+            Ok(()) => println!("Saved baseline to {}", path.display()),
+            Err(e) => {
+                eprintln!("{}", e);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
@@ -0,0 +1,102 @@
+use advent_of_code_2024::day1::{self, Order};
+use clap::ValueEnum;
+
+use crate::{table::Table, Input};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Sort {
+    /// Pairs by rank and contributions by ID.
+    Id,
+    /// Largest distance or contribution first.
+    Contribution,
+}
+
+impl From<Sort> for Order {
+    fn from(sort: Sort) -> Self {
+        match sort {
+            Sort::Id => Order::Id,
+            Sort::Contribution => Order::Contribution,
+        }
+    }
+}
+
+pub struct Options<'a> {
+    pub day: u32,
+    pub part: Option<u32>,
+    pub input: &'a Input,
+    pub sort: Sort,
+    /// Only print this many rows of each table.
+    pub top: Option<usize>,
+}
+
+/// Prints the sorted pairing behind part 1 and the per-ID contributions
+/// behind part 2 of day 1. Returns whether the input could be explained.
+fn explain_day1(options: &Options) -> bool {
+    let lists = match options.input.read(1).and_then(|text| {
+        day1::input_generator(&text).map_err(|e| format!("failed to parse day 1 input: {}", e))
+    }) {
+        Ok(lists) => lists,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let Some(mut explanation) = lists.explain(0, 1) else {
+        eprintln!("Day 1 input needs two columns to explain");
+        return false;
+    };
+    explanation.sort(options.sort.into());
+    let top = options.top.unwrap_or(usize::MAX);
+
+    if options.part.is_none_or(|part| part == 1) {
+        let mut table = Table::new(["Rank", "Left", "Right", "Distance"]);
+        for pair in explanation.pairs.iter().take(top) {
+            table.row([
+                (pair.rank + 1).to_string(),
+                pair.left.to_string(),
+                pair.right.to_string(),
+                pair.distance.to_string(),
+            ]);
+        }
+        table.row([
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            explanation.distance().to_string(),
+        ]);
+        println!("Part 1\n\n{}", table);
+    }
+
+    if options.part.is_none_or(|part| part == 2) {
+        let mut table = Table::new(["ID", "Left", "Right", "Contribution"]);
+        for contribution in explanation.contributions.iter().take(top) {
+            table.row([
+                contribution.id.to_string(),
+                contribution.left.to_string(),
+                contribution.right.to_string(),
+                contribution.score.to_string(),
+            ]);
+        }
+        table.row([
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            explanation.similarity().to_string(),
+        ]);
+        println!("Part 2\n\n{}", table);
+    }
+
+    true
+}
+
+/// Prints how a day's answers were worked out, for days that can explain
+/// them. Returns whether the day could be explained.
+pub fn explain(options: &Options) -> bool {
+    match options.day {
+        1 => explain_day1(options),
+        day => {
+            eprintln!("Day {} has no explain mode", day);
+            false
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use advent_of_code_2024::{
+    image::{GifWriter, Image},
+    render::Frame,
+};
+use clap::ValueEnum;
+
+use crate::{load, Input};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Png,
+    Ppm,
+    Gif,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Ppm => "ppm",
+            Format::Gif => "gif",
+        }
+    }
+
+    /// The format implied by `path`'s extension, defaulting to PNG.
+    fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => Format::Ppm,
+            Some("gif") => Format::Gif,
+            _ => Format::Png,
+        }
+    }
+}
+
+pub struct Options<'a> {
+    pub day: u32,
+    pub part: u32,
+    pub input: &'a Input,
+    /// Steps to simulate before the first exported frame.
+    pub at: Option<usize>,
+    /// How many frames to export; `None` exports the parsed input as drawn
+    /// by `show`, unless `at` is given.
+    pub frames: Option<usize>,
+    /// Steps to simulate between exported frames.
+    pub every: usize,
+    pub scale: u32,
+    pub format: Option<Format>,
+    pub output: &'a Path,
+    pub fps: f64,
+}
+
+/// Collects the frames to export: the drawn input, or frames of the day's
+/// simulation.
+fn frames(options: &Options) -> Result<Vec<Frame>, String> {
+    let (solution, parsed) = load(options.day, options.input)?;
+
+    if options.at.is_none() && options.frames.is_none() {
+        let frame = solution
+            .render_any(parsed.as_ref())
+            .ok_or_else(|| format!("Day {} has nothing to draw", options.day))?;
+        return Ok(vec![frame]);
+    }
+
+    let mut simulation = solution
+        .simulate_any(parsed.as_ref(), options.part)
+        .ok_or_else(|| format!("Day {} has no simulation", options.day))?;
+    for step in 0..options.at.unwrap_or(0) {
+        if !simulation.step() {
+            return Err(format!("simulation ended after {} steps", step));
+        }
+    }
+
+    let mut frames = vec![simulation.render()];
+    'frames: while frames.len() < options.frames.unwrap_or(1) {
+        for _ in 0..options.every.max(1) {
+            if !simulation.step() {
+                break 'frames;
+            }
+        }
+        frames.push(simulation.render());
+    }
+    Ok(frames)
+}
+
+/// Writes the frames as images, returning where they went. A single image
+/// gets the extension of its format, so it's saved as that format.
+fn write(frames: &[Frame], options: &Options) -> io::Result<PathBuf> {
+    let format = options.format.unwrap_or_else(|| Format::of(options.output));
+    let mut images = frames
+        .iter()
+        .map(|frame| Image::from_frame(frame, options.scale))
+        .peekable();
+
+    if format == Format::Gif {
+        let delay = Duration::try_from_secs_f64(1.0 / options.fps)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let first = images.peek().expect("at least one frame");
+        let mut gif = GifWriter::new(
+            BufWriter::new(File::create(options.output)?),
+            first.width(),
+            first.height(),
+            delay,
+        )?;
+        images.try_for_each(|image| gif.push(&image))?;
+        return Ok(options.output.to_path_buf());
+    }
+
+    if options.frames.is_none() {
+        let image = images.next().expect("at least one frame");
+        let path = if options.output.extension() == Some(format.extension().as_ref()) {
+            options.output.to_path_buf()
+        } else {
+            options.output.with_extension(format.extension())
+        };
+        image.save(&path)?;
+        return Ok(path);
+    }
+
+    // A sequence goes into a directory, one numbered file per frame.
+    fs::create_dir_all(options.output)?;
+    images.enumerate().try_for_each(|(i, image)| {
+        let name = format!("frame_{:05}.{}", i, format.extension());
+        image.save(options.output.join(name))
+    })?;
+    Ok(options.output.to_path_buf())
+}
+
+/// Draws a day's input or simulation frames as images. Returns whether they
+/// were written.
+pub fn export(options: &Options) -> bool {
+    let frames = match frames(options) {
+        Ok(frames) => frames,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    match write(&frames, options) {
+        Ok(path) => {
+            println!(
+                "Wrote {} frame{} to {}",
+                frames.len(),
+                if frames.len() == 1 { "" } else { "s" },
+                path.display()
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("failed to write {}: {}", options.output.display(), e);
+            false
+        }
+    }
+}
@@ -0,0 +1,738 @@
+use std::{
+    any::Any,
+    fs, panic,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use advent_of_code_2024::{
+    animate, differential, generate,
+    inputs::{self, Inputs},
+    runner::{self, Solver},
+    solution::{self, DynSolution},
+};
+use clap::{ArgAction, Parser, Subcommand};
+use itertools::Itertools;
+use tracing_subscriber::EnvFilter;
+
+mod bench;
+mod explain;
+mod export;
+mod memory;
+mod report;
+mod show;
+mod table;
+mod verify;
+mod watch;
+
+#[global_allocator]
+static ALLOCATOR: memory::Tracking = memory::Tracking;
+
+/// Runs the Advent of Code 2024 solutions without cargo-aoc.
+#[derive(Parser)]
+#[command(about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log what the solvers are doing to stderr: `-v` for debug events, `-vv`
+    /// for trace events. `AOC_LOG` takes a filter such as `day6=debug` instead.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// The puzzle year, which picks the directory under `input` to use.
+    #[arg(long, global = true, default_value_t = solution::YEAR)]
+    year: u32,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single day, or a single part of a day.
+    Run {
+        #[arg(short, long)]
+        day: u32,
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Read the puzzle input from this file instead of `input/<year>/dayN.txt`.
+        #[arg(short, long, conflicts_with_all = ["name", "example"])]
+        input: Option<PathBuf>,
+        /// Use the day's input with this name, as listed by `inputs`.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use the day's example from `fixtures/dayN/example.txt`, or the
+        /// fixture called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+    },
+    /// Run every part of every day in parallel, and report their answers,
+    /// timings and peak memory.
+    RunAll {
+        /// Use each day's input with this name instead of the real one.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use each day's example from `fixtures`, or the fixtures called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+        /// How many parts to run at once; defaults to one per core.
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Also write the report as JSON to this file, or only to stdout if
+        /// it's `-`.
+        #[arg(long, value_name = "PATH")]
+        json: Option<PathBuf>,
+    },
+    /// Check every solution against the answers in `input/<year>/answers.txt`.
+    Verify {
+        /// Only verify this day.
+        #[arg(short, long)]
+        day: Option<u32>,
+        /// Only verify this part.
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Verify the inputs with this name instead of the real ones.
+        #[arg(short, long, default_value = inputs::REAL, conflicts_with = "example")]
+        name: String,
+        /// Verify every fixture against its own answers instead of the real
+        /// inputs.
+        #[arg(short, long)]
+        example: bool,
+        /// Offer to record answers that are new or differ from the registry.
+        #[arg(short, long, conflicts_with = "example")]
+        record: bool,
+    },
+    /// Time parsing and solving every part against the real inputs.
+    Bench {
+        /// Only benchmark this day.
+        #[arg(short, long)]
+        day: Option<u32>,
+        /// The most times to run each part.
+        #[arg(short, long, default_value_t = 10)]
+        iterations: u32,
+        /// Stop running a part once this many seconds have been spent on it.
+        #[arg(long, default_value = "2", value_parser = seconds)]
+        budget: Duration,
+        /// Compare against a baseline saved by `--save-baseline`.
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Save these timings as a baseline for later comparison.
+        #[arg(long)]
+        save_baseline: Option<PathBuf>,
+        /// Percentage slowdown against the baseline reported as a regression.
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Draw a day's parsed input.
+    Show {
+        #[arg(short, long)]
+        day: u32,
+        /// Read the puzzle input from this file instead of `input/<year>/dayN.txt`.
+        #[arg(short, long, conflicts_with_all = ["name", "example"])]
+        input: Option<PathBuf>,
+        /// Use the day's input with this name, as listed by `inputs`.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use the day's example from `fixtures/dayN/example.txt`, or the
+        /// fixture called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+        /// How to draw the frame; defaults to ANSI colours on a terminal.
+        #[arg(short, long, value_enum)]
+        format: Option<show::Format>,
+        /// Write the plain text frame to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show how a day's answers were worked out, as tables.
+    Explain {
+        #[arg(short, long)]
+        day: u32,
+        /// Only explain this part.
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Read the puzzle input from this file instead of `input/<year>/dayN.txt`.
+        #[arg(short, long, conflicts_with_all = ["name", "example"])]
+        input: Option<PathBuf>,
+        /// Use the day's input with this name, as listed by `inputs`.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use the day's example from `fixtures/dayN/example.txt`, or the
+        /// fixture called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+        /// How to order the rows.
+        #[arg(short, long, value_enum, default_value_t = explain::Sort::Id)]
+        sort: explain::Sort,
+        /// Only show this many rows of each table.
+        #[arg(short, long)]
+        top: Option<usize>,
+    },
+    /// Play a day's simulation in the terminal, controlled by typing commands
+    /// followed by enter.
+    Animate {
+        #[arg(short, long)]
+        day: u32,
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        /// Read the puzzle input from this file instead of `input/<year>/dayN.txt`.
+        #[arg(short, long, conflicts_with_all = ["name", "example"])]
+        input: Option<PathBuf>,
+        /// Use the day's input with this name, as listed by `inputs`.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use the day's example from `fixtures/dayN/example.txt`, or the
+        /// fixture called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+        /// Frames per second while playing.
+        #[arg(short, long, default_value_t = 10.0, value_parser = fps)]
+        fps: f64,
+    },
+    /// Write a random input for a day, for stress testing.
+    Generate {
+        #[arg(short, long)]
+        day: u32,
+        /// The same seed always generates the same input.
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// How many times the size of the real input to make it.
+        #[arg(long, default_value_t = 1.0)]
+        scale: f64,
+        /// Write the input to this file instead of stdout.
+        #[arg(short, long, conflicts_with = "save")]
+        output: Option<PathBuf>,
+        /// Save the input as one of the day's inputs, under this name.
+        #[arg(long, value_name = "NAME")]
+        save: Option<String>,
+    },
+    /// Rebuild and verify a day against its examples and real input whenever
+    /// its source, inputs or fixtures change.
+    Watch {
+        #[arg(short, long)]
+        day: u32,
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Seconds between looks for changes.
+        #[arg(long, default_value = "0.5", value_parser = interval)]
+        interval: Duration,
+    },
+    /// List the inputs each day has, by the names `--name` takes.
+    Inputs {
+        /// Only list this day's inputs.
+        #[arg(short, long)]
+        day: Option<u32>,
+    },
+    /// Check optimized solutions against their reference implementations on
+    /// generated inputs, reporting the smallest input they disagree on.
+    Differential {
+        /// Only check this day.
+        #[arg(short, long)]
+        day: Option<u32>,
+        /// Only check this part.
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// The first seed to generate an input from.
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// How many inputs to generate for each part.
+        #[arg(short = 'n', long, default_value_t = 100)]
+        inputs: u64,
+        /// How many times the size of the real input to make each one.
+        #[arg(long, default_value_t = 0.01)]
+        scale: f64,
+    },
+    /// Save a day's parsed input, or frames of its simulation, as images.
+    Export {
+        #[arg(short, long)]
+        day: u32,
+        /// The part whose simulation to export, with `--at` or `--frames`.
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        /// Read the puzzle input from this file instead of `input/<year>/dayN.txt`.
+        #[arg(short, long, conflicts_with_all = ["name", "example"])]
+        input: Option<PathBuf>,
+        /// Use the day's input with this name, as listed by `inputs`.
+        #[arg(short, long, conflicts_with = "example")]
+        name: Option<String>,
+        /// Use the day's example from `fixtures/dayN/example.txt`, or the
+        /// fixture called NAME.
+        #[arg(short, long, value_name = "NAME", num_args = 0..=1, default_missing_value = "example")]
+        example: Option<String>,
+        /// Simulate this many steps before the first frame.
+        #[arg(long)]
+        at: Option<usize>,
+        /// Export this many frames of the simulation, as numbered files in the
+        /// output directory or as one GIF.
+        #[arg(long)]
+        frames: Option<usize>,
+        /// Simulate this many steps between frames.
+        #[arg(long, default_value_t = 1)]
+        every: usize,
+        /// Pixels per cell.
+        #[arg(long, default_value_t = 4)]
+        scale: u32,
+        /// The image format; defaults to the output's extension, or PNG.
+        #[arg(short, long, value_enum)]
+        format: Option<export::Format>,
+        /// The image or GIF to write, or the directory for a sequence of frames.
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Frames per second of a GIF.
+        #[arg(long, default_value_t = 10.0, value_parser = fps)]
+        fps: f64,
+    },
+}
+
+/// Parses a number of seconds, which can't be negative or too big for a
+/// [`Duration`].
+fn seconds(arg: &str) -> Result<Duration, String> {
+    let seconds = arg.parse::<f64>().map_err(|e| e.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+/// Parses the seconds between looks for changes, which has to be more than
+/// zero so watching doesn't spin.
+fn interval(arg: &str) -> Result<Duration, String> {
+    Some(seconds(arg)?)
+        .filter(|interval| !interval.is_zero())
+        .ok_or_else(|| "must be more than zero".to_string())
+}
+
+/// Parses a frame rate, which has to be one the player can space frames
+/// apart at.
+fn fps(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(fps) if animate::FPS.contains(&fps) => Ok(fps),
+        Ok(_) => Err(format!(
+            "must be from {} to {}",
+            animate::FPS.start(),
+            animate::FPS.end()
+        )),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The name of the input picked by a command's `--name` and `--example`
+/// options, defaulting to the real one.
+fn input_name(name: Option<String>, example: Option<String>) -> String {
+    match (name, example) {
+        (Some(name), _) => name,
+        (None, Some(example)) => format!("{}{}", inputs::EXAMPLE_PREFIX, example),
+        (None, None) => inputs::REAL.to_string(),
+    }
+}
+
+/// Where to read a day's puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Input {
+    File(PathBuf),
+    /// One of the day's inputs for a year, by name. See [`Inputs`].
+    Named {
+        year: u32,
+        name: String,
+    },
+}
+
+impl Input {
+    /// The input picked by a command's `--input`, `--name` and `--example`
+    /// options, defaulting to the real one.
+    fn new(
+        year: u32,
+        file: Option<PathBuf>,
+        name: Option<String>,
+        example: Option<String>,
+    ) -> Self {
+        match file {
+            Some(file) => Input::File(file),
+            None => Input::Named {
+                year,
+                name: input_name(name, example),
+            },
+        }
+    }
+
+    fn real(year: u32) -> Self {
+        Input::Named {
+            year,
+            name: inputs::REAL.to_string(),
+        }
+    }
+
+    fn read(&self, day: u32) -> Result<String, String> {
+        match self {
+            Input::File(path) => fs::read_to_string(path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e)),
+            Input::Named { year, name } => Inputs::default()
+                .read(*year, day, name)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Reads and parses `input` for `day`.
+fn load(day: u32, input: &Input) -> Result<(&'static dyn DynSolution, Box<dyn Any>), String> {
+    let solution = solution::solution(day).ok_or_else(|| format!("No solution for day {}", day))?;
+    let text = input.read(day)?;
+    let parsed = solution
+        .parse_any(&text)
+        .map_err(|e| format!("failed to parse day {} input: {}", day, e))?;
+    Ok((solution, parsed))
+}
+
+/// Plays `part` of `day` in the terminal. Returns whether the day could be
+/// animated.
+fn animate(day: u32, part: u32, input: &Input, fps: f64) -> bool {
+    let (solution, parsed) = match load(day, input) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let Some(simulation) = solution.simulate_any(parsed.as_ref(), part) else {
+        eprintln!("Day {} has no animation", day);
+        return false;
+    };
+
+    match animate::play(simulation, fps) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("failed to draw: {}", e);
+            false
+        }
+    }
+}
+
+/// Writes a random input for `day` to `output`, saves it as one of the
+/// day's inputs called `save`, or prints it. Returns whether the day has a
+/// generator and the input was written.
+fn generate(
+    year: u32,
+    day: u32,
+    seed: u64,
+    scale: f64,
+    output: Option<&Path>,
+    save: Option<&str>,
+) -> bool {
+    let Some(input) = generate::generate(day, seed, scale) else {
+        eprintln!("Day {} has no input generator", day);
+        return false;
+    };
+
+    if let Some(name) = save {
+        return match Inputs::default().write(year, day, name, &input) {
+            Ok(path) => {
+                println!("Saved day {} input `{}` to {}", day, name, path.display());
+                true
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                false
+            }
+        };
+    }
+
+    match output {
+        Some(output) => match fs::write(output, input) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("failed to write {}: {}", output.display(), e);
+                false
+            }
+        },
+        None => {
+            print!("{}", input);
+            true
+        }
+    }
+}
+
+/// Prints the names of the inputs for `day`, or every day that has any.
+/// Returns whether they could all be listed.
+fn list_inputs(year: u32, day: Option<u32>) -> bool {
+    let inputs = Inputs::default();
+    let mut ok = true;
+    let mut listed = false;
+
+    for day in day.map_or(1..=25, |day| day..=day) {
+        match inputs.names(year, day) {
+            Ok(names) if names.is_empty() => continue,
+            Ok(names) => {
+                println!("Day {}: {}", day, names.join(", "));
+                listed = true;
+            }
+            Err(e) => {
+                eprintln!("Day {}: {}", day, e);
+                ok = false;
+            }
+        }
+    }
+
+    if !listed && ok {
+        match day {
+            Some(day) => println!("Day {} of {} has no inputs", day, year),
+            None => println!("No inputs for {}", year),
+        }
+    }
+    ok
+}
+
+/// Checks each part with a reference implementation on `inputs` inputs from
+/// `seed` on, optionally only those of `day` or `part`. Returns whether they
+/// all agreed.
+fn differential(day: Option<u32>, part: Option<u32>, seed: u64, inputs: u64, scale: f64) -> bool {
+    let Some(end) = seed.checked_add(inputs) else {
+        eprintln!(
+            "{} inputs from seed {} run past the last seed",
+            inputs, seed
+        );
+        return false;
+    };
+    let seeds = seed..end;
+
+    let checks = differential::checkable()
+        .filter(|(solution, p)| {
+            day.is_none_or(|day| solution.day() == day) && part.is_none_or(|part| *p == part)
+        })
+        .collect_vec();
+    if checks.is_empty() {
+        eprintln!("Nothing has a reference implementation to check against");
+        return false;
+    }
+
+    // Panics are reported as disagreements, so don't also print them while
+    // shrinking.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut ok = true;
+    for (solution, part) in checks {
+        match differential::check(solution, part, seeds.clone(), scale) {
+            None => println!(
+                "Day {} part {}: agrees on {} inputs",
+                solution.day(),
+                part,
+                inputs
+            ),
+            Some(disagreement) => {
+                println!("{}", disagreement);
+                ok = false;
+            }
+        }
+    }
+
+    panic::set_hook(hook);
+    ok
+}
+
+/// Runs each solver against its day's `input`, printing the answers and
+/// timings. Returns whether every solver succeeded.
+fn run(solvers: impl Iterator<Item = Solver>, input: &Input) -> bool {
+    let mut ok = true;
+
+    for solver in solvers {
+        let label = format!("Day {} part {}", solver.day, solver.part);
+
+        let text = match input.read(solver.day) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("{}: {}", label, e);
+                ok = false;
+                continue;
+            }
+        };
+
+        match solver.run(&text) {
+            Ok(run) => println!(
+                "{}: {} (parse {:.2?}, solve {:.2?})",
+                label, run.answer, run.parse_time, run.solve_time
+            ),
+            Err(e) => {
+                eprintln!("{}: {}", label, e);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
+
+/// Installs a logger if asked for one, so solvers stay silent by default.
+fn init_tracing(verbose: u8) {
+    let filter = match std::env::var("AOC_LOG") {
+        Ok(filter) => EnvFilter::new(filter),
+        Err(_) => match verbose {
+            0 => return,
+            1 => EnvFilter::new("debug"),
+            _ => EnvFilter::new("trace"),
+        },
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbose);
+
+    let year = cli.year;
+    if year != solution::YEAR && !matches!(cli.command, Command::Inputs { .. }) {
+        eprintln!(
+            "No solutions for {}; only {} is solved so far",
+            year,
+            solution::YEAR
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let ok = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            name,
+            example,
+        } => {
+            let mut solvers = runner::find(day, part).peekable();
+            if solvers.peek().is_none() {
+                eprintln!("No solution for day {}", day);
+                return ExitCode::FAILURE;
+            }
+            run(solvers, &Input::new(year, input, name, example))
+        }
+        Command::RunAll {
+            name,
+            example,
+            jobs,
+            json,
+        } => report::report(&report::Options {
+            year,
+            name: &input_name(name, example),
+            jobs,
+            json: json.as_deref(),
+        }),
+        Command::Verify {
+            day,
+            part,
+            name,
+            example,
+            record,
+        } => verify::verify(&verify::Options {
+            year,
+            day,
+            part,
+            name: &name,
+            example,
+            record,
+        }),
+        Command::Bench {
+            day,
+            iterations,
+            budget,
+            baseline,
+            save_baseline,
+            threshold,
+        } => bench::bench(&bench::Options {
+            year,
+            day,
+            iterations,
+            budget,
+            baseline: baseline.as_deref(),
+            save_baseline: save_baseline.as_deref(),
+            threshold,
+        }),
+        Command::Show {
+            day,
+            input,
+            name,
+            example,
+            format,
+            output,
+        } => show::show(
+            day,
+            &Input::new(year, input, name, example),
+            format,
+            output.as_deref(),
+        ),
+        Command::Explain {
+            day,
+            part,
+            input,
+            name,
+            example,
+            sort,
+            top,
+        } => explain::explain(&explain::Options {
+            day,
+            part,
+            input: &Input::new(year, input, name, example),
+            sort,
+            top,
+        }),
+        Command::Animate {
+            day,
+            part,
+            input,
+            name,
+            example,
+            fps,
+        } => animate(day, part, &Input::new(year, input, name, example), fps),
+        Command::Generate {
+            day,
+            seed,
+            scale,
+            output,
+            save,
+        } => generate(year, day, seed, scale, output.as_deref(), save.as_deref()),
+        Command::Inputs { day } => list_inputs(year, day),
+        Command::Watch {
+            day,
+            part,
+            interval,
+        } => watch::watch(&watch::Options {
+            year,
+            day,
+            part,
+            interval,
+        }),
+        Command::Differential {
+            day,
+            part,
+            seed,
+            inputs,
+            scale,
+        } => differential(day, part, seed, inputs, scale),
+        Command::Export {
+            day,
+            part,
+            input,
+            name,
+            example,
+            at,
+            frames,
+            every,
+            scale,
+            format,
+            output,
+            fps,
+        } => export::export(&export::Options {
+            day,
+            part,
+            input: &Input::new(year, input, name, example),
+            at,
+            frames,
+            every,
+            scale,
+            format,
+            output: &output,
+            fps,
+        }),
+    };
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// The system allocator, counting how many bytes each thread has allocated
+/// so that [`peak`] can measure one run while others run on other threads.
+pub struct Tracking;
+
+thread_local! {
+    // Signed, since a thread can free memory another thread allocated.
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn grow(bytes: usize) {
+    let _ = CURRENT.try_with(|current| {
+        let now = current.get() + bytes as isize;
+        current.set(now);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(now)));
+    });
+}
+
+fn shrink(bytes: usize) {
+    let _ = CURRENT.try_with(|current| current.set(current.get() - bytes as isize));
+}
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            // Count both while the contents may be moving, as the peak would.
+            grow(new_size);
+            shrink(layout.size());
+        }
+        new
+    }
+}
+
+/// Runs `f`, returning its result and the most memory, in bytes, that it
+/// had allocated at once on this thread.
+pub fn peak<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let start = CURRENT.with(Cell::get);
+    let outer = PEAK.with(|peak| peak.replace(start));
+
+    let result = f();
+
+    let inner = PEAK.with(|peak| peak.replace(outer.max(peak.get())));
+    (result, (inner - start).max(0) as usize)
+}
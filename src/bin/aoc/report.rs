@@ -0,0 +1,281 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2024::{
+    answers::{Answers, Key},
+    fixture::Fixture,
+    inputs::{self, Inputs},
+    runner::{self, Solver},
+    Answer,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{memory, table::Table, verify::load_answers};
+
+pub struct Options<'a> {
+    pub year: u32,
+    /// The name of the input to run each day against.
+    pub name: &'a str,
+    /// How many parts to run at once; `None` uses every core.
+    pub jobs: Option<usize>,
+    /// Write the report as JSON to this file, or stdout if it's `-`.
+    pub json: Option<&'a Path>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Mismatch,
+    New,
+    Failed,
+}
+
+/// How one part of a day did.
+#[derive(Debug, Clone, Serialize)]
+struct Entry {
+    day: u32,
+    part: u32,
+    answer: Option<String>,
+    expected: Option<String>,
+    status: Status,
+    /// Why the part failed, if it did.
+    error: Option<String>,
+    parse_ns: u64,
+    solve_ns: u64,
+    /// The most memory the part had allocated at once while parsing and
+    /// solving.
+    peak_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Totals {
+    ok: usize,
+    mismatched: usize,
+    new: usize,
+    failed: usize,
+    parse_ns: u64,
+    solve_ns: u64,
+    /// The time the whole report took, which is less than the sum of the
+    /// parts' times when they run in parallel.
+    wall_ns: u64,
+    /// The largest peak of any one part.
+    peak_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Report {
+    year: u32,
+    input: String,
+    jobs: usize,
+    entries: Vec<Entry>,
+    totals: Totals,
+}
+
+/// The answer `solver` should give for the input called `name`: the
+/// fixture's answer for an example, and the registered answer otherwise.
+fn expected(solver: &Solver, name: &str, answers: &Answers) -> Option<Answer> {
+    match name.strip_prefix(inputs::EXAMPLE_PREFIX) {
+        Some(fixture) => Fixture::load(solver.day, fixture)
+            .ok()?
+            .answer(solver.part)
+            .cloned(),
+        None => answers
+            .get(&Key::new(solver.day, solver.part, name))
+            .cloned(),
+    }
+}
+
+fn entry(solver: &Solver, options: &Options, answers: &Answers) -> Entry {
+    let expected = expected(solver, options.name, answers);
+    let mut entry = Entry {
+        day: solver.day,
+        part: solver.part,
+        answer: None,
+        expected: expected.as_ref().map(Answer::to_string),
+        status: Status::Failed,
+        error: None,
+        parse_ns: 0,
+        solve_ns: 0,
+        peak_bytes: 0,
+    };
+
+    let input = match Inputs::default().read(options.year, solver.day, options.name) {
+        Ok(input) => input,
+        Err(e) => {
+            entry.error = Some(e.to_string());
+            return entry;
+        }
+    };
+
+    let (run, peak_bytes) = memory::peak(|| solver.run(&input));
+    entry.peak_bytes = peak_bytes;
+    match run {
+        Ok(run) => {
+            entry.status = match &expected {
+                Some(expected) if *expected == run.answer => Status::Ok,
+                Some(_) => Status::Mismatch,
+                None => Status::New,
+            };
+            entry.answer = Some(run.answer.to_string());
+            entry.parse_ns = run.parse_time.as_nanos() as u64;
+            entry.solve_ns = run.solve_time.as_nanos() as u64;
+        }
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    entry
+}
+
+fn totals(entries: &[Entry], wall: Duration) -> Totals {
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
+    Totals {
+        ok: count(Status::Ok),
+        mismatched: count(Status::Mismatch),
+        new: count(Status::New),
+        failed: count(Status::Failed),
+        parse_ns: entries.iter().map(|e| e.parse_ns).sum(),
+        solve_ns: entries.iter().map(|e| e.solve_ns).sum(),
+        wall_ns: wall.as_nanos() as u64,
+        peak_bytes: entries.iter().map(|e| e.peak_bytes).max().unwrap_or(0),
+    }
+}
+
+fn bytes(n: usize) -> String {
+    match n {
+        0..1024 => format!("{} B", n),
+        1024..1_048_576 => format!("{:.1} KiB", n as f64 / 1024.0),
+        _ => format!("{:.1} MiB", n as f64 / 1_048_576.0),
+    }
+}
+
+fn nanos(ns: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns))
+}
+
+fn print(report: &Report) {
+    let mut table = Table::new([
+        "Day", "Part", "Answer", "Status", "Parse", "Solve", "Memory",
+    ]);
+    for entry in &report.entries {
+        let status = match entry.status {
+            Status::Ok => "ok".to_string(),
+            Status::Mismatch => format!(
+                "MISMATCH, expected {}",
+                entry.expected.as_deref().unwrap_or_default()
+            ),
+            Status::New => "new".to_string(),
+            Status::Failed => format!("FAILED: {}", entry.error.as_deref().unwrap_or_default()),
+        };
+        table.row([
+            entry.day.to_string(),
+            entry.part.to_string(),
+            entry
+                .answer
+                .as_deref()
+                .map_or("-".to_string(), |a| a.replace('\n', "\\n")),
+            status,
+            nanos(entry.parse_ns),
+            nanos(entry.solve_ns),
+            bytes(entry.peak_bytes),
+        ]);
+    }
+
+    let totals = &report.totals;
+    table.row([
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        format!(
+            "{} ok, {} mismatched, {} new, {} failed",
+            totals.ok, totals.mismatched, totals.new, totals.failed
+        ),
+        nanos(totals.parse_ns),
+        nanos(totals.solve_ns),
+        bytes(totals.peak_bytes),
+    ]);
+
+    print!("{}", table);
+    println!(
+        "\nRan {} parts against `{}` in {} on {} thread{}",
+        report.entries.len(),
+        report.input,
+        nanos(totals.wall_ns),
+        report.jobs,
+        if report.jobs == 1 { "" } else { "s" }
+    );
+}
+
+/// Runs every part of every day, in parallel, and reports how each did.
+/// Returns whether nothing mismatched or failed.
+pub fn report(options: &Options) -> bool {
+    let answers = match load_answers(&Inputs::default().answers_path(options.year)) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()
+    {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("failed to start threads: {}", e);
+            return false;
+        }
+    };
+
+    // As with `verify`, a fixture only checks the parts it gives answers for.
+    let example = options.name.strip_prefix(inputs::EXAMPLE_PREFIX);
+    let solvers: Vec<Solver> = runner::solvers()
+        .filter(|solver| {
+            example.is_none_or(|name| {
+                Fixture::load(solver.day, name)
+                    .map_or(true, |fixture| fixture.answer(solver.part).is_some())
+            })
+        })
+        .collect();
+    let start = Instant::now();
+    let entries: Vec<Entry> = pool.install(|| {
+        solvers
+            .par_iter()
+            .map(|solver| entry(solver, options, &answers))
+            .collect()
+    });
+    let totals = totals(&entries, start.elapsed());
+    let ok = totals.mismatched == 0 && totals.failed == 0;
+
+    let report = Report {
+        year: options.year,
+        input: options.name.to_string(),
+        jobs: pool.current_num_threads(),
+        entries,
+        totals,
+    };
+
+    match options.json {
+        Some(path) if path == Path::new("-") => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("reports serialize")
+            );
+        }
+        Some(path) => {
+            print(&report);
+            let json = serde_json::to_string_pretty(&report).expect("reports serialize");
+            if let Err(e) = fs::write(path, json + "\n") {
+                eprintln!("failed to write {}: {}", path.display(), e);
+                return false;
+            }
+            println!("Wrote the report to {}", path.display());
+        }
+        None => print(&report),
+    }
+
+    ok
+}
@@ -0,0 +1,52 @@
+use std::{
+    io::{self, IsTerminal},
+    path::Path,
+};
+
+use clap::ValueEnum;
+
+use crate::{load, Input};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Plain,
+    Ansi,
+}
+
+/// Parses `input` and draws it, either to stdout or as a plain
+/// text dump to `output`. Returns whether the day could be drawn.
+pub fn show(day: u32, input: &Input, format: Option<Format>, output: Option<&Path>) -> bool {
+    let (solution, parsed) = match load(day, input) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let Some(frame) = solution.render_any(parsed.as_ref()) else {
+        eprintln!("Day {} has nothing to draw", day);
+        return false;
+    };
+
+    if let Some(output) = output {
+        return match frame.dump(output) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("failed to write {}: {}", output.display(), e);
+                false
+            }
+        };
+    }
+
+    let format = format.unwrap_or(if io::stdout().is_terminal() {
+        Format::Ansi
+    } else {
+        Format::Plain
+    });
+    match format {
+        Format::Plain => println!("{}", frame),
+        Format::Ansi => println!("{}", frame.to_ansi()),
+    }
+
+    true
+}
@@ -0,0 +1,237 @@
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    time::Duration,
+};
+
+use advent_of_code_2024::{
+    answers::{Answers, Key},
+    fixture,
+    inputs::Inputs,
+    runner::{self, Solver},
+    Answer,
+};
+
+use crate::table::Table;
+
+pub struct Options<'a> {
+    pub year: u32,
+    pub day: Option<u32>,
+    pub part: Option<u32>,
+    /// The inputs to verify, when not verifying the examples.
+    pub name: &'a str,
+    /// Verify every fixture against its own answers instead.
+    pub example: bool,
+    /// Offer to record answers that are new or differ from the registry.
+    pub record: bool,
+}
+
+enum Status {
+    Ok,
+    Mismatch,
+    New,
+    Failed(String),
+}
+
+struct Check {
+    key: Key,
+    expected: Option<Answer>,
+    answer: Option<Answer>,
+    /// How long parsing and solving took.
+    time: Option<Duration>,
+    status: Status,
+}
+
+pub fn load_answers(path: &Path) -> Result<Answers, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => contents
+            .parse()
+            .map_err(|e| format!("failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+        Err(e) => Err(format!("failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Runs `solver` on `input` and compares its answer with `expected`.
+fn check_one(
+    solver: &Solver,
+    key: Key,
+    input: Result<String, String>,
+    expected: Option<Answer>,
+) -> Check {
+    match input.and_then(|input| solver.run(&input).map_err(|e| e.to_string())) {
+        Ok(run) => {
+            let status = match &expected {
+                Some(expected) if *expected == run.answer => Status::Ok,
+                Some(_) => Status::Mismatch,
+                None => Status::New,
+            };
+            Check {
+                key,
+                expected,
+                answer: Some(run.answer),
+                time: Some(run.parse_time + run.solve_time),
+                status,
+            }
+        }
+        Err(e) => Check {
+            key,
+            expected,
+            answer: None,
+            time: None,
+            status: Status::Failed(e),
+        },
+    }
+}
+
+/// Checks the inputs called `options.name` against the registered answers,
+/// or with `options.example` every fixture against the answers it gives.
+fn check(options: &Options, answers: &Answers) -> Vec<Check> {
+    runner::solvers()
+        .filter(|solver| {
+            options.day.is_none_or(|day| solver.day == day)
+                && options.part.is_none_or(|part| solver.part == part)
+        })
+        .flat_map(|solver| {
+            if !options.example {
+                let key = Key::new(solver.day, solver.part, options.name);
+                let expected = answers.get(&key).cloned();
+                let input = Inputs::default()
+                    .read(options.year, solver.day, options.name)
+                    .map_err(|e| e.to_string());
+                return vec![check_one(&solver, key, input, expected)];
+            }
+
+            match fixture::load_day(solver.day) {
+                // A fixture only checks the parts it gives answers for.
+                Ok(fixtures) => fixtures
+                    .into_iter()
+                    .filter_map(|fixture| {
+                        let key = Key::new(solver.day, solver.part, fixture.name.as_str());
+                        let expected = fixture.answer(solver.part).cloned()?;
+                        Some(check_one(&solver, key, Ok(fixture.input), Some(expected)))
+                    })
+                    .collect(),
+                Err(e) => {
+                    let key = Key::new(solver.day, solver.part, "example");
+                    vec![check_one(&solver, key, Err(e.to_string()), None)]
+                }
+            }
+        })
+        .collect()
+}
+
+fn confirm(prompt: &str) -> bool {
+    print!("{} [y/N] ", prompt);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).is_ok() && line.trim().eq_ignore_ascii_case("y")
+}
+
+/// Asks about each new or changed answer and records the confirmed ones.
+/// Returns whether anything was recorded.
+fn record(checks: &[Check], answers: &mut Answers) -> bool {
+    let mut changed = false;
+
+    for check in checks {
+        let Some(answer) = &check.answer else {
+            continue;
+        };
+        let prompt = match &check.status {
+            Status::New => format!(
+                "Record {} for day {} part {} ({})?",
+                answer, check.key.day, check.key.part, check.key.input
+            ),
+            Status::Mismatch => format!(
+                "Replace {} with {} for day {} part {} ({})?",
+                check
+                    .expected
+                    .as_ref()
+                    .expect("a mismatch has an expected answer"),
+                answer,
+                check.key.day,
+                check.key.part,
+                check.key.input
+            ),
+            Status::Ok | Status::Failed(_) => continue,
+        };
+        if confirm(&prompt) {
+            answers.insert(check.key.clone(), answer.clone());
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Runs every solution against the registered answers and prints a table of
+/// the results. Returns whether nothing mismatched or failed.
+pub fn verify(options: &Options) -> bool {
+    let path = Inputs::default().answers_path(options.year);
+    let mut answers = match load_answers(&path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let checks = check(options, &answers);
+
+    let mut table = Table::new([
+        "Day", "Part", "Input", "Expected", "Actual", "Time", "Status",
+    ]);
+    let (mut mismatched, mut new, mut failed) = (0, 0, 0);
+    for check in &checks {
+        let show = |answer: Option<&Answer>| {
+            answer.map_or("-".to_string(), |a| a.to_string().replace('\n', "\\n"))
+        };
+        let expected = show(check.expected.as_ref());
+        let actual = show(check.answer.as_ref());
+        let status = match &check.status {
+            Status::Ok => "ok".to_string(),
+            Status::Mismatch => {
+                mismatched += 1;
+                "MISMATCH".to_string()
+            }
+            Status::New => {
+                new += 1;
+                "new".to_string()
+            }
+            Status::Failed(e) => {
+                failed += 1;
+                format!("FAILED: {}", e)
+            }
+        };
+        table.row([
+            check.key.day.to_string(),
+            check.key.part.to_string(),
+            check.key.input.clone(),
+            expected,
+            actual,
+            check.time.map_or("-".to_string(), |t| format!("{:.2?}", t)),
+            status,
+        ]);
+    }
+
+    print!("{}", table);
+    println!(
+        "\n{} checked: {} ok, {} mismatched, {} new, {} failed",
+        checks.len(),
+        checks.len() - mismatched - new - failed,
+        mismatched,
+        new,
+        failed
+    );
+
+    if options.record && record(&checks, &mut answers) {
+        if let Err(e) = fs::write(&path, answers.to_string()) {
+            eprintln!("failed to write {}: {}", path.display(), e);
+            return false;
+        }
+        println!("Updated {}", path.display());
+    }
+
+    mismatched == 0 && failed == 0
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use advent_of_code_2024::{
+    fixture,
+    inputs::{self, Inputs},
+    ROOT,
+};
+
+pub struct Options {
+    pub year: u32,
+    pub day: u32,
+    pub part: Option<u32>,
+    /// How often to look for changes.
+    pub interval: Duration,
+}
+
+/// When each watched file was last modified. Files that disappear drop out,
+/// which counts as a change too.
+type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// The files whose changes re-run the day: its source, its inputs and
+/// fixtures, and the answers they're checked against. Directories are listed
+/// again on every look, so new inputs are noticed.
+fn watched(options: &Options) -> Vec<PathBuf> {
+    let inputs = Inputs::default();
+    let listed = |dir: PathBuf| -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect()
+    };
+
+    let mut files = vec![
+        Path::new(ROOT).join(format!("src/day{}.rs", options.day)),
+        inputs.answers_path(options.year),
+        inputs.path(options.year, options.day, inputs::REAL),
+    ];
+    files.extend(listed(inputs.day_dir(options.year, options.day)));
+    files.extend(listed(
+        Path::new(fixture::DIR).join(format!("day{}", options.day)),
+    ));
+    files
+}
+
+fn snapshot(options: &Options) -> Snapshot {
+    watched(options)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// The files that were added, changed or removed between two snapshots.
+fn changes(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let changed = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(*modified))
+        .map(|(path, _)| path.clone());
+    let removed = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned();
+    changed.chain(removed).collect()
+}
+
+/// Cargo, run from [`ROOT`] so it finds the crate from any directory.
+fn cargo() -> Command {
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo.current_dir(ROOT);
+    cargo
+}
+
+/// Runs `aoc` with `args` through cargo, so it's the freshly built binary.
+fn aoc(year: u32, args: &[String]) -> io::Result<bool> {
+    let status = cargo()
+        .args(["run", "--release", "--quiet", "--bin", "aoc", "--"])
+        .args(["--year", &year.to_string()])
+        .args(args)
+        .status()?;
+    Ok(status.success())
+}
+
+/// Rebuilds, then verifies the day against its examples and its real input.
+/// Returns whether everything built and passed.
+fn check(options: &Options) -> io::Result<bool> {
+    let start = Instant::now();
+    println!("Building...");
+    let built = cargo()
+        .args(["build", "--release", "--quiet", "--bin", "aoc"])
+        .status()?
+        .success();
+    if !built {
+        println!("\nBuild failed");
+        return Ok(false);
+    }
+
+    let mut verify = vec!["verify".to_string(), format!("--day={}", options.day)];
+    if let Some(part) = options.part {
+        verify.push(format!("--part={}", part));
+    }
+
+    println!("\nExamples:");
+    let examples = aoc(
+        options.year,
+        &[&verify[..], &["--example".to_string()]].concat(),
+    )?;
+    println!("\nReal input:");
+    let real = aoc(options.year, &verify)?;
+
+    let passed = examples && real;
+    println!(
+        "\n{} in {:.1?}",
+        if passed { "PASS" } else { "FAIL" },
+        start.elapsed()
+    );
+    Ok(passed)
+}
+
+/// Rebuilds and re-checks the day whenever its source or inputs change, until
+/// interrupted. Returns only if the checks can't be run at all.
+pub fn watch(options: &Options) -> bool {
+    let mut seen = snapshot(options);
+    let mut changed: Vec<PathBuf> = Vec::new();
+
+    loop {
+        let _ = clearscreen::clear();
+        match changed.as_slice() {
+            [] => println!("Day {}", options.day),
+            [path] => println!("Day {}: {} changed", options.day, path.display()),
+            paths => println!("Day {}: {} files changed", options.day, paths.len()),
+        }
+        if let Err(e) = check(options) {
+            eprintln!("failed to run cargo: {}", e);
+            return false;
+        }
+        println!("\nWatching for changes, press ctrl-c to stop");
+
+        loop {
+            thread::sleep(options.interval);
+            let now = snapshot(options);
+            changed = changes(&seen, &now);
+            seen = now;
+            if !changed.is_empty() {
+                break;
+            }
+        }
+    }
+}
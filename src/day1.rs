@@ -1,47 +1,982 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+    str::FromStr,
+};
+
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
 use nom::{
-    character::complete::{multispace1, newline, u32},
-    multi::separated_list0,
-    sequence::separated_pair,
+    branch::alt,
+    character::complete::{char, space0, space1, u32},
+    combinator::value,
+    multi::separated_list1,
+    sequence::delimited,
+};
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt};
+
+use crate::{
+    answer::Answer,
+    generate::count,
+    parse::{finish, IResult, ParseError},
+    solution::Solution,
 };
-use std::collections::BTreeMap;
+
+/// Lists of location IDs, one per column. Every row has the same number of
+/// columns, so the lists are all the same length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationLists {
+    columns: Vec<Vec<u32>>,
+}
+
+/// A way of scoring how alike two lists are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// The sum of the differences between the lists' IDs, smallest paired
+    /// with smallest. Part 1's answer.
+    Distance,
+    /// Each ID in the first list times how often it's in the second. Part 2's
+    /// answer.
+    Similarity,
+    /// How many IDs the lists share, counting repeats as often as both have
+    /// them.
+    Intersection,
+    /// The Euclidean distance between the sorted lists.
+    L2,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [
+        Metric::Distance,
+        Metric::Similarity,
+        Metric::Intersection,
+        Metric::L2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Distance => "distance",
+            Metric::Similarity => "similarity",
+            Metric::Intersection => "intersection",
+            Metric::L2 => "l2",
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A metric name that isn't one of [`Metric::ALL`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMetric(pub String);
+
+impl Display for UnknownMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown metric `{}`, expected one of {}",
+            self.0,
+            Metric::ALL.iter().join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownMetric {}
+
+impl FromStr for Metric {
+    type Err = UnknownMetric;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Metric::ALL
+            .into_iter()
+            .find(|metric| metric.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| UnknownMetric(name.to_string()))
+    }
+}
+
+/// Columns are separated by spaces, tabs or a comma.
+fn separator(input: &str) -> IResult<&str, ()> {
+    alt((
+        value((), delimited(space0, char(','), space0)),
+        value((), space1),
+    ))(input)
+}
+
+fn row(input: &str) -> IResult<&str, Vec<u32>> {
+    delimited(space0, separated_list1(separator, u32), space0)(input)
+}
+
+fn counts(list: &[u32]) -> BTreeMap<u32, u64> {
+    let mut counts = BTreeMap::new();
+    for id in list {
+        *counts.entry(*id).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn sorted(list: &[u32]) -> Vec<u32> {
+    let mut list = list.to_vec();
+    list.sort_unstable();
+    list
+}
+
+impl FromStr for LocationLists {
+    type Err = ParseError;
+
+    /// Each line is parsed on its own, so a malformed line is reported
+    /// rather than ending the lists early.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut rows: Vec<Vec<u32>> = Vec::new();
+        for (i, line) in input.trim_end().lines().enumerate() {
+            let at_line = |e| ParseError { line: i + 1, ..e };
+            let row = finish(line, row).map_err(at_line)?;
+            if let Some(first) = rows.first().filter(|first| first.len() != row.len()) {
+                return Err(at_line(ParseError::new(
+                    line,
+                    line,
+                    format!("{} columns, as on line 1", first.len()),
+                )));
+            }
+            rows.push(row);
+        }
+        if rows.is_empty() {
+            return Err(ParseError::new(input, "", "a number"));
+        }
+
+        let width = rows[0].len();
+        let columns = (0..width)
+            .map(|column| rows.iter().map(|row| row[column]).collect())
+            .collect();
+        Ok(Self { columns })
+    }
+}
+
+impl LocationLists {
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn column(&self, column: usize) -> Option<&[u32]> {
+        self.columns.get(column).map(Vec::as_slice)
+    }
+
+    fn pair(&self, a: usize, b: usize) -> Option<(&[u32], &[u32])> {
+        Some((self.column(a)?, self.column(b)?))
+    }
+
+    /// The total distance between columns `a` and `b`, or `None` if either
+    /// doesn't exist. See [`Metric::Distance`].
+    pub fn distance(&self, a: usize, b: usize) -> Option<u64> {
+        let (a, b) = self.pair(a, b)?;
+        Some(
+            sorted(a)
+                .iter()
+                .zip(sorted(b).iter())
+                .map(|(a, b)| a.abs_diff(*b) as u64)
+                .sum(),
+        )
+    }
+
+    /// The similarity score of column `a` against column `b`, or `None` if
+    /// either doesn't exist. See [`Metric::Similarity`].
+    pub fn similarity(&self, a: usize, b: usize) -> Option<u64> {
+        let (a, b) = self.pair(a, b)?;
+        let counts = counts(b);
+        Some(
+            a.iter()
+                .map(|id| *id as u64 * counts.get(id).unwrap_or(&0))
+                .sum(),
+        )
+    }
+
+    /// How many IDs columns `a` and `b` share, or `None` if either doesn't
+    /// exist. See [`Metric::Intersection`].
+    pub fn intersection(&self, a: usize, b: usize) -> Option<u64> {
+        let (a, b) = self.pair(a, b)?;
+        let counts = counts(b);
+        Some(
+            self::counts(a)
+                .iter()
+                .map(|(id, n)| *n.min(counts.get(id).unwrap_or(&0)))
+                .sum(),
+        )
+    }
+
+    /// The Euclidean distance between columns `a` and `b`, or `None` if
+    /// either doesn't exist. See [`Metric::L2`].
+    pub fn l2(&self, a: usize, b: usize) -> Option<f64> {
+        let (a, b) = self.pair(a, b)?;
+        let squares: f64 = sorted(a)
+            .iter()
+            .zip(sorted(b).iter())
+            .map(|(a, b)| (a.abs_diff(*b) as f64).powi(2))
+            .sum();
+        Some(squares.sqrt())
+    }
+
+    /// An index of columns `a` and `b` that can be edited, or `None` if
+    /// either doesn't exist.
+    pub fn index(&self, a: usize, b: usize) -> Option<LocationIndex> {
+        let (a, b) = self.pair(a, b)?;
+        let mut index = LocationIndex::new();
+        for id in a {
+            index.insert(Side::Left, *id);
+        }
+        for id in b {
+            index.insert(Side::Right, *id);
+        }
+        Some(index)
+    }
+
+    /// The pairs and contributions behind the distance and similarity of
+    /// columns `a` and `b`, in [`Order::Id`], or `None` if either doesn't
+    /// exist.
+    pub fn explain(&self, a: usize, b: usize) -> Option<Explanation> {
+        let (a, b) = self.pair(a, b)?;
+        let pairs = sorted(a)
+            .into_iter()
+            .zip(sorted(b))
+            .enumerate()
+            .map(|(rank, (left, right))| Pair {
+                rank,
+                left,
+                right,
+                distance: left.abs_diff(right) as u64,
+            })
+            .collect();
+        let right = counts(b);
+        let contributions = counts(a)
+            .into_iter()
+            .map(|(id, left)| {
+                let right = right.get(&id).copied().unwrap_or(0);
+                Contribution {
+                    id,
+                    left,
+                    right,
+                    score: id as u64 * left * right,
+                }
+            })
+            .collect();
+        Some(Explanation {
+            pairs,
+            contributions,
+        })
+    }
+
+    /// Scores columns `a` and `b` with `metric`, or `None` if either column
+    /// doesn't exist.
+    pub fn score(&self, metric: Metric, a: usize, b: usize) -> Option<f64> {
+        match metric {
+            Metric::Distance => self.distance(a, b).map(|n| n as f64),
+            Metric::Similarity => self.similarity(a, b).map(|n| n as f64),
+            Metric::Intersection => self.intersection(a, b).map(|n| n as f64),
+            Metric::L2 => self.l2(a, b),
+        }
+    }
+}
+
+/// Two IDs paired by sorting their lists, as part 1 pairs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+    /// The 0-based position of both IDs in their sorted lists.
+    pub rank: usize,
+    pub left: u32,
+    pub right: u32,
+    pub distance: u64,
+}
+
+/// What one left ID adds to the similarity score, as part 2 scores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    pub id: u32,
+    /// How many times the ID is in the left list.
+    pub left: u64,
+    /// How many times the ID is in the right list.
+    pub right: u64,
+    /// The ID times both counts.
+    pub score: u64,
+}
+
+/// How to order an [`Explanation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Pairs by rank and contributions by ID, as they're worked out.
+    Id,
+    /// Largest first: pairs by distance and contributions by score. Ties
+    /// stay in ID order.
+    Contribution,
+}
+
+/// How the distance and similarity of two lists were worked out, to track
+/// down which IDs an answer depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub pairs: Vec<Pair>,
+    /// One for each distinct left ID.
+    pub contributions: Vec<Contribution>,
+}
+
+impl Explanation {
+    /// The total of the pairs' distances.
+    pub fn distance(&self) -> u64 {
+        self.pairs.iter().map(|pair| pair.distance).sum()
+    }
+
+    /// The total of the contributions.
+    pub fn similarity(&self) -> u64 {
+        self.contributions.iter().map(|c| c.score).sum()
+    }
+
+    pub fn sort(&mut self, order: Order) {
+        match order {
+            Order::Id => {
+                self.pairs.sort_by_key(|pair| pair.rank);
+                self.contributions.sort_by_key(|c| c.id);
+            }
+            Order::Contribution => {
+                self.pairs
+                    .sort_by_key(|pair| (Reverse(pair.distance), pair.rank));
+                self.contributions.sort_by_key(|c| (Reverse(c.score), c.id));
+            }
+        }
+    }
+}
+
+/// Which of the two lists in a [`LocationIndex`] an ID is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A change to a [`LocationIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Insert(Side, u32),
+    Remove(Side, u32),
+}
+
+/// One past the largest ID.
+const END: u64 = 1 << 32;
+
+/// The fewest stretches a [`Block`] is split at.
+const MIN_BLOCK: usize = 64;
+
+/// A run of IDs over which the difference between how many left IDs and how
+/// many right IDs are at most each ID is the same.
+#[derive(Debug, Clone, Copy)]
+struct Stretch {
+    start: u32,
+    len: u64,
+    /// Not counting the offset of the block it's in.
+    difference: i64,
+}
+
+/// Consecutive stretches, with how long the stretches with each difference
+/// are in total, so a change to all of them only needs to look up those it
+/// moves across zero.
+#[derive(Debug, Clone)]
+struct Block {
+    stretches: Vec<Stretch>,
+    /// Added to the difference of every stretch in the block.
+    offset: i64,
+    len: u64,
+    /// The total length of the stretches with each difference from `lowest`
+    /// up, not counting the offset. Neighbouring stretches' differences are
+    /// close together, so this stays about as long as the block.
+    lens: VecDeque<u64>,
+    lowest: i64,
+    /// The total length of the stretches whose difference isn't negative.
+    nonnegative: u64,
+}
+
+impl Block {
+    fn new(stretches: Vec<Stretch>, offset: i64) -> Self {
+        let mut block = Self {
+            stretches: Vec::new(),
+            offset,
+            len: 0,
+            lens: VecDeque::new(),
+            lowest: 0,
+            nonnegative: 0,
+        };
+        for stretch in stretches {
+            block.add(&stretch);
+            block.stretches.push(stretch);
+        }
+        block
+    }
+
+    /// Counts `stretch` towards the block's totals.
+    fn add(&mut self, stretch: &Stretch) {
+        self.len += stretch.len;
+        if self.lens.is_empty() {
+            self.lowest = stretch.difference;
+        }
+        while stretch.difference < self.lowest {
+            self.lens.push_front(0);
+            self.lowest -= 1;
+        }
+        let i = (stretch.difference - self.lowest) as usize;
+        if i >= self.lens.len() {
+            self.lens.resize(i + 1, 0);
+        }
+        self.lens[i] += stretch.len;
+        if stretch.difference + self.offset >= 0 {
+            self.nonnegative += stretch.len;
+        }
+    }
+
+    /// Stops counting `stretch` towards the block's totals.
+    fn remove(&mut self, stretch: &Stretch) {
+        self.len -= stretch.len;
+        self.lens[(stretch.difference - self.lowest) as usize] -= stretch.len;
+        while self.lens.front() == Some(&0) {
+            self.lens.pop_front();
+            self.lowest += 1;
+        }
+        while self.lens.back() == Some(&0) {
+            self.lens.pop_back();
+        }
+        if stretch.difference + self.offset >= 0 {
+            self.nonnegative -= stretch.len;
+        }
+    }
+
+    /// The total length of the stretches whose difference is exactly
+    /// `difference`.
+    fn len_of(&self, difference: i64) -> u64 {
+        usize::try_from(difference - self.offset - self.lowest)
+            .ok()
+            .and_then(|i| self.lens.get(i))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Every ID split into stretches, kept in blocks of about the square root of
+/// their number. Moving the difference of every stretch from some ID up by
+/// one updates the stretches after it in its block one by one, and each
+/// block after that as a whole, so takes O(√n) time for n stretches.
+#[derive(Debug, Clone)]
+struct Stretches {
+    blocks: Vec<Block>,
+    count: usize,
+    /// The sum of each stretch's length times its absolute difference.
+    area: u64,
+}
+
+impl Default for Stretches {
+    fn default() -> Self {
+        let whole = Stretch {
+            start: 0,
+            len: END,
+            difference: 0,
+        };
+        Self {
+            blocks: vec![Block::new(vec![whole], 0)],
+            count: 1,
+            area: 0,
+        }
+    }
+}
+
+impl Stretches {
+    /// The most stretches a block holds before it's split in two.
+    fn max_block(&self) -> usize {
+        2 * MIN_BLOCK.max(self.count.isqrt())
+    }
+
+    /// Moves the difference of every stretch from `id` up by one, up or
+    /// down, splitting the stretch `id` is in if it doesn't start there.
+    /// Returns how many stretches and blocks it had to visit.
+    fn shift(&mut self, id: u32, up: bool) -> usize {
+        let change = if up { 1 } else { -1 };
+        let max_block = self.max_block();
+        let b = self
+            .blocks
+            .partition_point(|block| block.stretches[0].start <= id)
+            - 1;
+
+        let block = &mut self.blocks[b];
+        let mut s = block
+            .stretches
+            .partition_point(|stretch| stretch.start <= id)
+            - 1;
+        let stretch = &mut block.stretches[s];
+        if stretch.start != id {
+            let end = stretch.start as u64 + stretch.len;
+            stretch.len = (id - stretch.start) as u64;
+            let split_off = Stretch {
+                start: id,
+                len: end - id as u64,
+                difference: stretch.difference,
+            };
+            s += 1;
+            block.stretches.insert(s, split_off);
+            self.count += 1;
+        }
+
+        let mut visited = block.stretches.len() - s;
+        for i in s..block.stretches.len() {
+            let mut stretch = block.stretches[i];
+            block.remove(&stretch);
+            let before = (stretch.difference + block.offset).unsigned_abs();
+            stretch.difference += change;
+            let after = (stretch.difference + block.offset).unsigned_abs();
+            self.area = self.area + stretch.len * after - stretch.len * before;
+            block.add(&stretch);
+            block.stretches[i] = stretch;
+        }
+
+        let mut next = b + 1;
+        if block.stretches.len() > max_block {
+            visited += block.stretches.len();
+            let rest = block.stretches.split_off(block.stretches.len() / 2);
+            let offset = block.offset;
+            *block = Block::new(std::mem::take(&mut block.stretches), offset);
+            self.blocks.insert(next, Block::new(rest, offset));
+            next += 1;
+        }
+
+        // Moving up adds one to the absolute difference of every stretch
+        // that isn't negative and takes one from the rest, and moving down
+        // adds one to those that aren't positive.
+        visited += self.blocks.len() - next;
+        for block in &mut self.blocks[next..] {
+            let zero = block.len_of(0);
+            let growing = if up {
+                block.nonnegative
+            } else {
+                block.len - block.nonnegative + zero
+            };
+            self.area = self.area + growing - (block.len - growing);
+            block.offset += change;
+            if up {
+                block.nonnegative += block.len_of(0);
+            } else {
+                block.nonnegative -= zero;
+            }
+        }
+        visited
+    }
+}
+
+/// Two lists of location IDs that can be edited one ID at a time, keeping
+/// their total distance and similarity score up to date.
+///
+/// The distance is kept as the area between the lists' cumulative counts:
+/// summed over every `t`, the difference between how many left IDs and how
+/// many right IDs are at most `t`. For lists of the same length that's the
+/// distance of pairing them in sorted order. An edit to an ID adds one to or
+/// takes one from the difference of every ID from it up, which
+/// [`Stretches`] does without visiting every ID, and the similarity score
+/// only changes by the edited ID times how often it's on the other side.
+#[derive(Debug, Clone, Default)]
+pub struct LocationIndex {
+    left: BTreeMap<u32, u64>,
+    right: BTreeMap<u32, u64>,
+    left_len: u64,
+    right_len: u64,
+    stretches: Stretches,
+    similarity: u64,
+}
+
+/// Indexes are equal if they hold the same IDs, however they were edited.
+impl PartialEq for LocationIndex {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.right == other.right
+    }
+}
+
+impl Eq for LocationIndex {}
+
+impl LocationIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn counts(&self, side: Side) -> &BTreeMap<u32, u64> {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+
+    fn other(side: Side) -> Side {
+        match side {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    /// How many IDs are on `side`, counting repeats.
+    pub fn len(&self, side: Side) -> u64 {
+        match side {
+            Side::Left => self.left_len,
+            Side::Right => self.right_len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left_len == 0 && self.right_len == 0
+    }
+
+    /// How many times `id` is on `side`.
+    pub fn count(&self, side: Side, id: u32) -> u64 {
+        self.counts(side).get(&id).copied().unwrap_or(0)
+    }
+
+    /// The total distance between the lists, or `None` while they're
+    /// different lengths and can't all be paired.
+    pub fn distance(&self) -> Option<u64> {
+        (self.left_len == self.right_len).then_some(self.stretches.area)
+    }
+
+    /// Each left ID times how often it's on the right.
+    pub fn similarity(&self) -> u64 {
+        self.similarity
+    }
+
+    pub fn insert(&mut self, side: Side, id: u32) {
+        self.stretches.shift(id, side == Side::Left);
+        self.similarity += id as u64 * self.count(Self::other(side), id);
+
+        let (counts, len) = match side {
+            Side::Left => (&mut self.left, &mut self.left_len),
+            Side::Right => (&mut self.right, &mut self.right_len),
+        };
+        *counts.entry(id).or_insert(0) += 1;
+        *len += 1;
+    }
+
+    /// Removes one `id` from `side`, returning whether there was one.
+    pub fn remove(&mut self, side: Side, id: u32) -> bool {
+        if self.count(side, id) == 0 {
+            return false;
+        }
+        self.stretches.shift(id, side == Side::Right);
+        self.similarity -= id as u64 * self.count(Self::other(side), id);
+
+        let (counts, len) = match side {
+            Side::Left => (&mut self.left, &mut self.left_len),
+            Side::Right => (&mut self.right, &mut self.right_len),
+        };
+        let count = counts.get_mut(&id).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&id);
+        }
+        *len -= 1;
+        true
+    }
+
+    /// Applies `edit`, returning whether it changed anything. Only removing
+    /// an ID that isn't there doesn't.
+    pub fn apply(&mut self, edit: Edit) -> bool {
+        match edit {
+            Edit::Insert(side, id) => {
+                self.insert(side, id);
+                true
+            }
+            Edit::Remove(side, id) => self.remove(side, id),
+        }
+    }
+}
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> (Vec<u32>, Vec<u32>) {
-    separated_list0(
-        newline::<&str, nom::error::Error<&str>>,
-        separated_pair(u32, multispace1, u32),
-    )(input)
-    .map(|(_, result)| result.iter().cloned().collect())
-    .unwrap()
+pub fn input_generator(input: &str) -> Result<LocationLists, ParseError> {
+    LocationLists::from_str(input)
 }
 
 #[aoc(day1, part1)]
-pub fn solve_part1(input: &(Vec<u32>, Vec<u32>)) -> u32 {
-    let mut left = input.0.clone();
-    left.sort();
-    let mut right = input.1.clone();
-    right.sort();
+pub fn solve_part1(lists: &LocationLists) -> Option<u64> {
+    lists.distance(0, 1)
+}
+
+#[aoc(day1, part2)]
+pub fn solve_part2(lists: &LocationLists) -> Option<u64> {
+    lists.similarity(0, 1)
+}
 
+/// Random location lists, with some IDs on the right copied from the left so
+/// the similarity score isn't zero.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let left: Vec<u32> = (0..count(1000, scale))
+        .map(|_| rng.random_range(10000..100000))
+        .collect();
     left.iter()
-        .zip(right.iter())
-        .map(|(l, r)| l.abs_diff(*r))
-        .sum()
+        .map(|l| {
+            let r = if rng.random_bool(0.4) {
+                *left.choose(rng).unwrap()
+            } else {
+                rng.random_range(10000..100000)
+            };
+            format!("{}   {}\n", l, r)
+        })
+        .collect()
 }
 
-#[aoc(day1, part2)]
-pub fn solve_part2(input: &(Vec<u32>, Vec<u32>)) -> u32 {
-    let mut occurances: BTreeMap<u32, u32> = BTreeMap::new();
-    for x in input.1.iter() {
-        occurances
-            .entry(*x)
-            .and_modify(|curr| *curr += 1)
-            .or_insert(1);
-    }
-
-    input
-        .0
-        .iter()
-        .map(|x| x * occurances.get(x).unwrap_or(&0))
-        .sum()
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = LocationLists;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        solve_part1(input).map(Answer::from)
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        solve_part2(input).map(Answer::from)
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_location_lists_parse() {
+        let lists = LocationLists::from_str("1 2, 3\n4\t5,6 \n  7 , 8 9\n").unwrap();
+        assert_eq!(lists.num_columns(), 3);
+        assert_eq!(lists.column(0), Some(&[1, 4, 7][..]));
+        assert_eq!(lists.column(2), Some(&[3, 6, 9][..]));
+        assert_eq!(lists.column(3), None);
+
+        let error = LocationLists::from_str("1 2\n3 4\n5 6 7\n8 9\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected 2 columns, as on line 1 at \"5 6 7\""
+        );
+
+        let error = LocationLists::from_str("1 2\n3 x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(LocationLists::from_str("\n").is_err());
+    }
+
+    #[test]
+    fn test_location_lists_metrics() {
+        let lists = LocationLists::from_str("3 4 3\n4 3 4\n2 5 2\n1 3 1\n3 9 3\n3 3 3\n").unwrap();
+        assert_eq!(lists.distance(0, 1), Some(11));
+        assert_eq!(lists.similarity(0, 1), Some(31));
+        assert_eq!(lists.intersection(0, 1), Some(4));
+        assert_eq!(lists.distance(0, 2), Some(0));
+        assert_eq!(lists.l2(0, 2), Some(0.0));
+        assert_eq!(lists.l2(0, 1), Some(35f64.sqrt()));
+        assert_eq!(lists.score(Metric::Similarity, 1, 0), Some(31.0));
+        assert_eq!(lists.distance(0, 3), None);
+
+        assert_eq!(
+            solve_part1(&LocationLists::from_str("1\n2\n").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metric_names() {
+        for metric in Metric::ALL {
+            assert_eq!(metric.to_string().parse(), Ok(metric));
+        }
+        assert_eq!("L2".parse(), Ok(Metric::L2));
+        assert_eq!(
+            "cosine".parse::<Metric>().unwrap_err().to_string(),
+            "unknown metric `cosine`, expected one of distance, similarity, intersection, l2"
+        );
+    }
+
+    #[test]
+    fn test_location_index() {
+        let lists = LocationLists::from_str("3 4\n4 3\n2 5\n1 3\n3 9\n3 3\n").unwrap();
+        let mut index = LocationIndex::new();
+        for (l, r) in lists
+            .column(0)
+            .unwrap()
+            .iter()
+            .zip(lists.column(1).unwrap())
+        {
+            index.insert(Side::Left, *l);
+            assert_eq!(index.distance(), None);
+            index.insert(Side::Right, *r);
+        }
+        assert_eq!(index.distance(), Some(11));
+        assert_eq!(index.similarity(), 31);
+        assert_eq!(lists.index(0, 1), Some(index.clone()));
+
+        assert!(index.remove(Side::Right, 9));
+        assert!(!index.remove(Side::Right, 9));
+        assert!(index.apply(Edit::Insert(Side::Right, 4)));
+        assert_eq!(index.distance(), Some(6));
+        assert_eq!(index.similarity(), 35);
+    }
+
+    #[test]
+    fn test_location_index_scales() {
+        const IDS: usize = 32_000;
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut ids = || -> Vec<u32> {
+            (0..IDS)
+                .map(|_| rng.random_range(10_000..100_000))
+                .collect()
+        };
+        let (left, right) = (ids(), ids());
+
+        let mut index = LocationIndex::new();
+        for (l, r) in left.iter().zip(&right) {
+            index.insert(Side::Left, *l);
+            index.insert(Side::Right, *r);
+        }
+        let (distance, similarity) = scores(&left, &right);
+        assert_eq!(index.distance(), distance);
+        assert_eq!(index.similarity(), similarity);
+
+        // Edits to low IDs change the difference of almost every stretch,
+        // but only visit a few times the square root of how many there are:
+        // the rest of a block, which may be split, and each later block.
+        let stretches = index.stretches.count;
+        let mut most_visited = 0;
+        for id in 0..1000 {
+            for up in [true, false] {
+                most_visited = most_visited.max(index.stretches.shift(id, up));
+            }
+
+            index.insert(Side::Left, id);
+            assert_eq!(index.distance(), None);
+            index.remove(Side::Left, id);
+            assert_eq!(index.distance(), distance);
+        }
+        assert!(stretches > IDS);
+        assert!(
+            most_visited <= 8 * stretches.isqrt(),
+            "visited {} of {} stretches",
+            most_visited,
+            stretches
+        );
+    }
+
+    /// The distance and similarity of two lists worked out from scratch.
+    fn scores(left: &[u32], right: &[u32]) -> (Option<u64>, u64) {
+        let distance = (left.len() == right.len()).then(|| {
+            sorted(left)
+                .iter()
+                .zip(sorted(right))
+                .map(|(l, r)| l.abs_diff(r) as u64)
+                .sum()
+        });
+        let right = counts(right);
+        let similarity = left
+            .iter()
+            .map(|id| *id as u64 * right.get(id).unwrap_or(&0))
+            .sum();
+        (distance, similarity)
+    }
+
+    fn edit() -> impl Strategy<Value = Edit> {
+        let side = prop_oneof![Just(Side::Left), Just(Side::Right)];
+        let id = prop_oneof![0..20u32, Just(u32::MAX), any::<u32>()];
+        (any::<bool>(), side, id).prop_map(|(insert, side, id)| {
+            if insert {
+                Edit::Insert(side, id)
+            } else {
+                Edit::Remove(side, id)
+            }
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_location_index_matches_lists(edits in prop::collection::vec(edit(), 0..60)) {
+            let mut index = LocationIndex::new();
+            let mut lists = [Vec::new(), Vec::new()];
+            for edit in edits {
+                let (side, id, insert) = match edit {
+                    Edit::Insert(side, id) => (side, id, true),
+                    Edit::Remove(side, id) => (side, id, false),
+                };
+                let list = &mut lists[side as usize];
+                let changed = if insert {
+                    list.push(id);
+                    true
+                } else if let Some(i) = list.iter().position(|x| *x == id) {
+                    list.swap_remove(i);
+                    true
+                } else {
+                    false
+                };
+
+                prop_assert_eq!(index.apply(edit), changed);
+                let (distance, similarity) = scores(&lists[0], &lists[1]);
+                prop_assert_eq!(index.distance(), distance);
+                prop_assert_eq!(index.similarity(), similarity);
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let lists = LocationLists::from_str("3 4\n4 3\n2 5\n1 3\n3 9\n3 3\n").unwrap();
+        let mut explanation = lists.explain(0, 1).unwrap();
+        assert_eq!(
+            explanation.pairs[0],
+            Pair {
+                rank: 0,
+                left: 1,
+                right: 3,
+                distance: 2
+            }
+        );
+        assert_eq!(explanation.distance(), 11);
+        assert_eq!(explanation.similarity(), 31);
+        assert_eq!(
+            explanation.contributions.iter().map(|c| c.id).collect_vec(),
+            [1, 2, 3, 4]
+        );
+
+        explanation.sort(Order::Contribution);
+        assert_eq!(
+            explanation
+                .pairs
+                .iter()
+                .map(|pair| (pair.rank, pair.distance))
+                .collect_vec(),
+            [(5, 5), (0, 2), (4, 2), (1, 1), (3, 1), (2, 0)]
+        );
+        assert_eq!(
+            explanation.contributions[..2],
+            [
+                Contribution {
+                    id: 3,
+                    left: 3,
+                    right: 3,
+                    score: 27
+                },
+                Contribution {
+                    id: 4,
+                    left: 1,
+                    right: 1,
+                    score: 4
+                }
+            ]
+        );
+
+        explanation.sort(Order::Id);
+        assert_eq!(explanation, lists.explain(0, 1).unwrap());
+    }
 }
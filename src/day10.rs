@@ -1,34 +1,29 @@
+use std::collections::VecDeque;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
 use itertools::Itertools;
-use nom::{
-    character::complete::{line_ending, satisfy},
-    multi::many1,
-    sequence::terminated,
-    IResult, Parser,
+use nom::{character::complete::satisfy, Parser};
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    answer::Answer,
+    generate::{char_grid, side},
+    grid::Grid,
+    parse::{finish, ParseError},
+    solution::Solution,
 };
-use vecgrid::Vecgrid;
-
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
-    }
-}
 
 type Trail = Vec<IVec2>;
 
 #[derive(Debug)]
-struct Map {
-    grid: Vecgrid<u8>,
+pub struct Map {
+    grid: Grid<u8>,
 }
 
 impl Map {
     fn get_point(&self, position: IVec2) -> Option<u8> {
-        self.grid
-            .get(position.y as usize, position.x as usize)
-            .copied()
+        self.grid.get(position).copied()
     }
 
     fn trails_from(&self, position: IVec2, previous: &Trail) -> Vec<Trail> {
@@ -39,15 +34,15 @@ impl Map {
                 previous.push(position);
 
                 if current == 9 {
-                    return vec![previous];
+                    vec![previous]
                 } else {
-                    [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
-                        .into_iter()
-                        .filter(|direction| {
-                            self.get_point(position + direction)
+                    self.grid
+                        .neighbours4(position)
+                        .filter(|&neighbour| {
+                            self.get_point(neighbour)
                                 .is_some_and(|point| point == current + 1)
                         })
-                        .flat_map(|direction| self.trails_from(position + direction, &previous))
+                        .flat_map(|neighbour| self.trails_from(neighbour, &previous))
                         .filter(|trail| {
                             trail
                                 .last()
@@ -61,12 +56,10 @@ impl Map {
 
     fn trails(&self) -> Vec<Trail> {
         self.grid
-            .enumerate_column_major()
+            .iter()
             .filter_map(|(position, point)| {
                 if *point == 0 {
-                    Some(
-                        self.trails_from(IVec2::new(position.1 as i32, position.0 as i32), &vec![]),
-                    )
+                    Some(self.trails_from(position, &vec![]))
                 } else {
                     None
                 }
@@ -77,17 +70,13 @@ impl Map {
 }
 
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Map {
-    let rows = many1(terminated(
-        many1(satisfy(|c| c.is_digit(10)).map(|c| c.to_digit(10).unwrap() as u8)),
-        end_of_line,
-    ))(input)
-    .unwrap()
-    .1;
-
-    Map {
-        grid: Vecgrid::from_rows(rows).expect("invalid input"),
-    }
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    let grid = finish(
+        input,
+        Grid::parse(satisfy(|c| c.is_ascii_digit()).map(|c| c.to_digit(10).unwrap() as u8)),
+    )?;
+
+    Ok(Map { grid })
 }
 
 #[aoc(day10, part1)]
@@ -111,38 +100,85 @@ pub fn solve_part2(map: &Map) -> usize {
         .sum()
 }
 
+/// A random map of hills. Random heights rarely climb all the way, so cells
+/// near a peak fall away from it one step at a time, and the rest are noise.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(58, scale);
+    let mut heights: Vec<Option<u32>> = vec![None; side * side];
+
+    let mut queue: VecDeque<(usize, usize)> = (0..side * side / 100)
+        .map(|_| (rng.random_range(0..side), rng.random_range(0..side)))
+        .collect();
+    for &(x, y) in &queue {
+        heights[y * side + x] = Some(9);
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let height = heights[y * side + x].unwrap();
+        if height == 0 {
+            continue;
+        }
+        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                continue;
+            };
+            if nx < side && ny < side && heights[ny * side + nx].is_none() {
+                heights[ny * side + nx] = Some(height - 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    char_grid(side, side, |x, y| {
+        let height = heights[y * side + x]
+            .filter(|_| rng.random_bool(0.9))
+            .unwrap_or_else(|| rng.random_range(0..10));
+        char::from_digit(height, 10).unwrap()
+    })
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixture::Fixture;
 
     #[test]
     fn test_day10_part1() {
-        let input = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
+        let input = Fixture::load(10, "example").unwrap().input;
 
-        let map = input_generator(&input);
+        let map = input_generator(&input).unwrap();
 
         assert_eq!(solve_part1(&map), 36);
     }
 
     #[test]
     fn test_day10_part2() {
-        let input = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
-
-        let map = input_generator(&input);
+        let input = Fixture::load(10, "example").unwrap().input;
+
+        let map = input_generator(&input).unwrap();
 
         assert_eq!(solve_part2(&map), 81);
     }
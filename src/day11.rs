@@ -1,12 +1,15 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, str::FromStr};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use nom::{
-    character::complete::{line_ending, space1, u64},
-    multi::separated_list1,
-    sequence::terminated,
-    IResult,
+use nom::character::complete::u64;
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    answer::Answer,
+    generate::{count, number},
+    parse::{finish, spaced, ParseError},
+    solution::Solution,
 };
 
 #[derive(Clone)]
@@ -14,14 +17,14 @@ pub struct Stones {
     stones: BTreeMap<u64, usize>,
 }
 
-impl Stones {
-    fn from_str(input: &str) -> Self {
-        let stones = terminated(separated_list1(space1, u64), end_of_line)(input)
-            .unwrap()
-            .1;
-        Self {
+impl FromStr for Stones {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let stones = finish(input, spaced(u64))?;
+        Ok(Self {
             stones: stones.into_iter().counts().into_iter().collect(),
-        }
+        })
     }
 }
 
@@ -59,16 +62,8 @@ impl Iterator for Stones {
     }
 }
 
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
-    }
-}
-
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Stones {
+pub fn input_generator(input: &str) -> Result<Stones, ParseError> {
     Stones::from_str(input)
 }
 
@@ -84,17 +79,70 @@ pub fn solve_part2(stones: &Stones) -> usize {
     stones.nth(75).unwrap()
 }
 
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(8, scale))
+        .map(|_| {
+            if rng.random_bool(0.1) {
+                0
+            } else {
+                number(rng, 1, 7)
+            }
+        })
+        .join(" ")
+        + "\n"
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Stones;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::fixture::Fixture;
 
     #[test]
     fn test_stones() {
-        let input = "125 17";
-        let mut stones = Stones::from_str(input);
+        let input = Fixture::load(11, "example").unwrap().input;
+        let mut stones = Stones::from_str(&input).unwrap();
         assert_eq!(stones.next(), Some(2));
         assert_eq!(stones.next(), Some(3));
         assert_eq!(stones.next(), Some(4));
         assert_eq!(stones.nth(22), Some(55312));
     }
+
+    proptest! {
+        #[test]
+        fn test_stones_never_decrease(stones in prop::collection::vec(0..1_000_000_000u64, 1..10)) {
+            let input = stones.iter().join(" ");
+            let counts: Vec<usize> = Stones::from_str(&input).unwrap().take(25).collect();
+
+            prop_assert_eq!(counts[0], stones.len());
+            for (before, after) in counts.iter().tuple_windows() {
+                prop_assert!(after >= before, "{} stones became {}", before, after);
+            }
+        }
+    }
 }
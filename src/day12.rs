@@ -1,34 +1,34 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
 use itertools::Itertools;
-use nom::{
-    character::complete::{line_ending, satisfy},
-    multi::many1,
-    sequence::terminated,
-    IResult,
+use nom::character::complete::satisfy;
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    answer::Answer,
+    direction::Direction,
+    generate::{char_grid, side},
+    grid::Grid,
+    parse::{finish, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::Solution,
 };
-use vecgrid::Vecgrid;
-
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
-    }
-}
 
 #[derive(Clone, Debug)]
 pub struct Map {
-    grid: Vecgrid<char>,
+    grid: Grid<char>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Plot {
     position: IVec2,
     plant: char,
-    edges: Vec<IVec2>,
+    edges: Vec<Direction>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -46,8 +46,8 @@ impl Region {
         self.plots.iter().map(|plot| plot.edges.len()).sum()
     }
 
-    fn edges_at(&self, position: IVec2, side: IVec2) -> Vec<Vec<i32>> {
-        let column = side.x != 0;
+    fn edges_at(&self, position: IVec2, side: Direction) -> Vec<Vec<i32>> {
+        let column = side.is_horizontal();
         let mut result = Vec::new();
         let mut current_edge = Vec::new();
 
@@ -59,7 +59,7 @@ impl Region {
                     && p.edges.contains(&side)
             })
             .map(|p| if column { p.position.y } else { p.position.x })
-            .sorted_by(|a, b| a.cmp(&b))
+            .sorted()
         {
             if current_edge.is_empty() || p == current_edge.last().unwrap() + 1 {
                 current_edge.push(p);
@@ -76,7 +76,7 @@ impl Region {
         result
     }
 
-    fn num_edges_at(&self, position: IVec2, side: IVec2) -> usize {
+    fn num_edges_at(&self, position: IVec2, side: Direction) -> usize {
         self.edges_at(position, side).len()
     }
 
@@ -89,15 +89,15 @@ impl Region {
             if !visited_columns.contains(&plot.position.x) {
                 visited_columns.insert(plot.position.x);
 
-                result += self.num_edges_at(plot.position, IVec2::NEG_X)
-                    + self.num_edges_at(plot.position, IVec2::X);
+                result += self.num_edges_at(plot.position, Direction::W)
+                    + self.num_edges_at(plot.position, Direction::E);
             }
 
             if !visited_rows.contains(&plot.position.y) {
                 visited_rows.insert(plot.position.y);
 
-                result += self.num_edges_at(plot.position, IVec2::NEG_Y)
-                    + self.num_edges_at(plot.position, IVec2::Y);
+                result += self.num_edges_at(plot.position, Direction::N)
+                    + self.num_edges_at(plot.position, Direction::S);
             }
         }
 
@@ -117,26 +117,19 @@ impl Region {
     }
 }
 
-const ALL_DIRECTIONS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+impl FromStr for Map {
+    type Err = ParseError;
 
-impl Map {
-    fn from_str(input: &str) -> Self {
-        let rows = many1(terminated(
-            many1(satisfy(|c| c.is_alphanumeric())),
-            end_of_line,
-        ))(input)
-        .unwrap()
-        .1;
-
-        Self {
-            grid: Vecgrid::from_rows(rows).expect("invalid input"),
-        }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let grid = finish(input, Grid::parse(satisfy(|c| c.is_alphanumeric())))?;
+
+        Ok(Self { grid })
     }
+}
 
+impl Map {
     fn get_point(&self, position: IVec2) -> Option<char> {
-        self.grid
-            .get(position.y as usize, position.x as usize)
-            .copied()
+        self.grid.get(position).copied()
     }
 
     fn march_region(&self, start: IVec2) -> Option<Region> {
@@ -152,13 +145,16 @@ impl Map {
                 continue;
             };
 
-            if c != initial_c || plots.iter().find(|plot| plot.position == p).is_some() {
+            if c != initial_c || plots.iter().any(|plot| plot.position == p) {
                 continue;
             }
 
-            let edges = ALL_DIRECTIONS
+            let edges = Direction::CARDINAL
                 .into_iter()
-                .filter(|d| !self.get_point(p + d).is_some_and(|c| c == initial_c))
+                .filter(|&d| {
+                    self.get_point(p + IVec2::from(d))
+                        .is_none_or(|c| c != initial_c)
+                })
                 .collect();
 
             plots.insert(Plot {
@@ -167,9 +163,7 @@ impl Map {
                 edges,
             });
 
-            for d in ALL_DIRECTIONS {
-                queue.push_back(p + d);
-            }
+            queue.extend(self.grid.neighbours4(p));
         }
 
         Some(Region {
@@ -181,8 +175,7 @@ impl Map {
     fn regions(&self) -> Vec<Region> {
         let mut regions: Vec<Region> = Vec::new();
 
-        for ((y, x), _) in self.grid.enumerate_column_major() {
-            let p = IVec2::new(x as i32, y as i32);
+        for p in self.grid.positions() {
             if regions.iter().any(|r| r.has_plot(p)) {
                 continue;
             }
@@ -208,8 +201,18 @@ impl Map {
     }
 }
 
+/// Each plant in its own colour, so regions stand out.
+impl Render for Map {
+    fn render(&self) -> Frame {
+        Frame::new(
+            self.grid
+                .map(|&plant| Cell::new(plant).with_colour(Colour::distinct(plant as usize))),
+        )
+    }
+}
+
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Map {
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
     Map::from_str(input)
 }
 
@@ -223,40 +226,80 @@ pub fn solve_part2(map: &Map) -> usize {
     map.fence_cost_edges()
 }
 
+/// A random garden. Regions grow out from scattered seedlings, claiming a
+/// random cell on their edge at a time so they come out irregular.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(140, scale);
+    let mut plants: Vec<Option<char>> = vec![None; side * side];
+
+    let mut edge: Vec<(usize, usize)> = Vec::new();
+    for _ in 0..(side * side / 30).max(1) {
+        let (x, y) = (rng.random_range(0..side), rng.random_range(0..side));
+        plants[y * side + x] = Some(rng.random_range('A'..='Z'));
+        edge.push((x, y));
+    }
+    while !edge.is_empty() {
+        let (x, y) = edge.swap_remove(rng.random_range(0..edge.len()));
+        let plant = plants[y * side + x];
+        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                continue;
+            };
+            if nx < side && ny < side && plants[ny * side + nx].is_none() {
+                plants[ny * side + nx] = plant;
+                edge.push((nx, ny));
+            }
+        }
+    }
+
+    char_grid(side, side, |x, y| plants[y * side + x].unwrap())
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::fixture::Fixture;
 
     #[test]
     fn test_cost_perimeter() {
-        let input = "RRRRIICCFF
-RRRRIICCCF
-VVRRRCCFFF
-VVRCCCJFFF
-VVVVCJJCFE
-VVIVCCJJEE
-VVIIICJJEE
-MIIIIIJJEE
-MIIISIJEEE
-MMMISSJEEE";
-        let map = Map::from_str(input);
+        let input = Fixture::load(12, "example").unwrap().input;
+        let map = Map::from_str(&input).unwrap();
         assert_eq!(map.regions().len(), 11);
         assert_eq!(map.fence_cost_perimeter(), 1930);
     }
 
     #[test]
     fn test_cost_edges() {
-        let input = "RRRRIICCFF
-RRRRIICCCF
-VVRRRCCFFF
-VVRCCCJFFF
-VVVVCJJCFE
-VVIVCCJJEE
-VVIIICJJEE
-MIIIIIJJEE
-MIIISIJEEE
-MMMISSJEEE";
-        let map = Map::from_str(input);
+        let input = Fixture::load(12, "example").unwrap().input;
+        let map = Map::from_str(&input).unwrap();
         let regions = map.regions();
         assert_eq!(regions.len(), 11);
         assert_eq!(map.fence_cost_edges(), 1206);
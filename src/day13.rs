@@ -1,23 +1,21 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::I64Vec2;
+use itertools::Itertools;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{i64, line_ending, newline},
-    multi::separated_list1,
+    character::complete::{i64, line_ending},
     sequence::{delimited, preceded, separated_pair, tuple},
-    IResult,
 };
+use rand::{rngs::StdRng, RngExt};
 
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
-    }
-}
+use crate::{
+    answer::Answer,
+    generate::count,
+    parse::{finish, sections, tag, IResult, ParseError},
+    solution::Solution,
+};
 
 #[derive(Debug, Clone, Copy)]
-struct Machine {
+pub struct Machine {
     a: I64Vec2,
     b: I64Vec2,
     prize: I64Vec2,
@@ -34,7 +32,8 @@ impl Machine {
 
         let b = (py * ax - px * ay) / (by * ax - bx * ay);
         let a = (px - bx * b) / ax;
-        if ax * a + bx * b == px && ay * a + by * b == py {
+        // Buttons can't be pressed a negative number of times.
+        if a >= 0 && b >= 0 && ax * a + bx * b == px && ay * a + by * b == py {
             Some(a * 3 + b)
         } else {
             None
@@ -47,17 +46,16 @@ fn parse_machine(input: &str) -> IResult<&str, Machine> {
         delimited(
             tag("Button A: "),
             separated_pair(preceded(tag("X+"), i64), tag(", Y+"), i64),
-            end_of_line,
+            line_ending,
         ),
         delimited(
             tag("Button B: "),
             separated_pair(preceded(tag("X+"), i64), tag(", Y+"), i64),
-            end_of_line,
+            line_ending,
         ),
-        delimited(
+        preceded(
             tag("Prize: "),
             separated_pair(preceded(tag("X="), i64), tag(", Y="), i64),
-            end_of_line,
         ),
     ))(input)?;
 
@@ -72,12 +70,12 @@ fn parse_machine(input: &str) -> IResult<&str, Machine> {
 }
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Vec<Machine> {
-    separated_list1(newline, parse_machine)(input).unwrap().1
+pub fn input_generator(input: &str) -> Result<Vec<Machine>, ParseError> {
+    finish(input, sections(parse_machine))
 }
 
 #[aoc(day13, part1)]
-pub fn solve_part1(machines: &Vec<Machine>) -> i64 {
+pub fn solve_part1(machines: &[Machine]) -> i64 {
     machines
         .iter()
         .map(|m| m.cheapest_solution_cost().unwrap_or(0))
@@ -85,47 +83,85 @@ pub fn solve_part1(machines: &Vec<Machine>) -> i64 {
 }
 
 #[aoc(day13, part2)]
-pub fn solve_part2(machines: &Vec<Machine>) -> i64 {
-    let machines = machines.clone();
+pub fn solve_part2(machines: &[Machine]) -> i64 {
     machines
         .iter()
         .map(|m| {
-            let mut m = m.clone();
+            let mut m = *m;
             m.prize += I64Vec2::new(10000000000000, 10000000000000);
             m.cheapest_solution_cost().unwrap_or(0)
         })
         .sum()
 }
 
+/// Random machines, some of whose prizes can be won.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(320, scale))
+        .map(|_| {
+            // Buttons that move in the same direction have no single solution.
+            let (a, b) = loop {
+                let a = I64Vec2::new(rng.random_range(10..100), rng.random_range(10..100));
+                let b = I64Vec2::new(rng.random_range(10..100), rng.random_range(10..100));
+                if a.perp_dot(b) != 0 {
+                    break (a, b);
+                }
+            };
+            let prize = if rng.random_bool(0.4) {
+                a * rng.random_range(1..=100) + b * rng.random_range(1..=100)
+            } else {
+                I64Vec2::new(rng.random_range(1000..20000), rng.random_range(1000..20000))
+            };
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                a.x, a.y, b.x, b.y, prize.x, prize.y
+            )
+        })
+        .join("\n")
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = Vec<Machine>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use itertools::Itertools;
 
     use super::*;
+    use crate::fixture::Fixture;
 
     #[test]
     fn test_day13_input_generator() {
-        let input = "Button A: X+94, Y+34
-Button B: X+22, Y+67
-Prize: X=8400, Y=5400
-
-Button A: X+26, Y+66
-Button B: X+67, Y+21
-Prize: X=12748, Y=12176
-
-Button A: X+17, Y+86
-Button B: X+84, Y+37
-Prize: X=7870, Y=6450
-
-Button A: X+69, Y+23
-Button B: X+27, Y+71
-Prize: X=18641, Y=10279
-";
-        let machines = input_generator(input);
-        dbg!(&machines);
-        dbg!(machines
-            .iter()
-            .map(|m| m.cheapest_solution_cost())
-            .collect_vec());
+        let input = Fixture::load(13, "example").unwrap().input;
+        let machines = input_generator(&input).unwrap();
+        assert_eq!(machines.len(), 4);
+        assert_eq!(machines[3].prize, I64Vec2::new(18641, 10279));
+        assert_eq!(
+            machines
+                .iter()
+                .map(|m| m.cheapest_solution_cost())
+                .collect_vec(),
+            [Some(280), None, Some(200), None]
+        );
     }
 }
@@ -1,45 +1,64 @@
-use core::time;
-use std::{fmt::Display, thread};
+use std::fmt::Display;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
 use itertools::Itertools;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{i32, line_ending, newline, space1},
-    multi::separated_list1,
-    sequence::{preceded, separated_pair},
-    IResult,
+    character::complete::{line_ending, space1},
+    combinator::{opt, verify},
+    sequence::{delimited, pair, preceded, separated_pair},
 };
+use rand::{rngs::StdRng, RngExt};
+use tracing::{debug, trace};
 
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
-    }
-}
+use crate::{
+    animate::Simulation,
+    answer::Answer,
+    generate::count,
+    grid::Grid,
+    parse::{finish, ivec2, lines, tag, IResult, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::Solution,
+};
 
-#[derive(Debug, Clone, Copy)]
-struct Robot {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
     pos: IVec2,
     vel: IVec2,
 }
 
-fn parse_ivec2(input: &str) -> IResult<&str, IVec2> {
-    let (input, (x, y)) = separated_pair(i32, tag(","), i32)(input)?;
-    Ok((input, IVec2::new(x, y)))
+/// Writes the robot the way the input does, as `p=x,y v=x,y`.
+impl Display for Robot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p={},{} v={},{}",
+            self.pos.x, self.pos.y, self.vel.x, self.vel.y
+        )
+    }
 }
 
 fn parse_robot(input: &str) -> IResult<&str, Robot> {
     let (input, (pos, vel)) = separated_pair(
-        preceded(tag("p="), parse_ivec2),
+        preceded(tag("p="), ivec2),
         space1,
-        preceded(tag("v="), parse_ivec2),
+        preceded(tag("v="), ivec2),
     )(input)?;
     Ok((input, Robot { pos, vel }))
 }
 
+/// A `size=w,h` line giving the size of the room.
+fn parse_size(input: &str) -> IResult<&str, IVec2> {
+    delimited(
+        tag("size="),
+        verify(ivec2, |size: &IVec2| size.cmpgt(IVec2::ZERO).all()),
+        line_ending,
+    )(input)
+}
+
+/// The size of the room the robots are in, unless the input says otherwise.
+const SIZE: IVec2 = IVec2::new(101, 103);
+
 fn wrap(x: i32, min: i32, max: i32) -> i32 {
     min + (x - min).rem_euclid(max - min + 1)
 }
@@ -81,8 +100,8 @@ impl Map {
         self.robots
             .iter()
             .into_group_map_by(|r| r.pos.x)
-            .into_iter()
-            .map(|(_, robots)| {
+            .into_values()
+            .map(|robots| {
                 let mut largest_span = 0;
                 let mut current_span = 0;
                 let mut last_y = None;
@@ -91,7 +110,7 @@ impl Map {
                     .sorted_by(|a, b| a.pos.y.cmp(&b.pos.y))
                     .map(|r| r.pos.y)
                 {
-                    if last_y.is_some() && y == last_y.unwrap() + 1 {
+                    if last_y.is_some_and(|last_y| y == last_y + 1) {
                         current_span += 1;
                     } else {
                         current_span = 1;
@@ -106,46 +125,59 @@ impl Map {
             >= 5
     }
 
+    fn robot_counts(&self) -> Grid<usize> {
+        let mut counts = Grid::new(self.size, 0);
+        for robot in &self.robots {
+            if let Some(count) = counts.get_mut(robot.pos) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+
     fn safety_factor(&self) -> usize {
         let counts = self.quadrant_counts();
         counts.iter().product()
     }
 }
 
+impl Simulation for Map {
+    fn step(&mut self) -> bool {
+        Map::step(self, 1);
+        true
+    }
+}
+
+/// The number of robots on each tile, with `+` for more than nine.
+impl Render for Map {
+    fn render(&self) -> Frame {
+        Frame::new(self.robot_counts().map(|&num| match num {
+            0 => Cell::new('.'),
+            1..=9 => Cell::new(char::from(b'0' + num as u8)).with_colour(Colour::Green),
+            _ => Cell::new('+').with_colour(Colour::Green).highlighted(),
+        }))
+    }
+}
+
 impl Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.size.y {
-            for x in 0..self.size.x {
-                let num = self
-                    .robots
-                    .iter()
-                    .filter(|r| r.pos == IVec2::new(x, y))
-                    .count();
-                if num > 0 {
-                    write!(f, "{}", num)?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            if y < self.size.y - 1 {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.render())
     }
 }
 
+/// Parses the robots, in a room of [`SIZE`] unless the input starts with a
+/// `size=w,h` line, as the puzzle's example is in a smaller room.
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Vec<Robot> {
-    separated_list1(newline, parse_robot)(input).unwrap().1
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    finish(input, pair(opt(parse_size), lines(parse_robot))).map(|(size, robots)| Map {
+        size: size.unwrap_or(SIZE),
+        robots,
+    })
 }
 
 #[aoc(day14, part1)]
-pub fn solve_part1(machines: &Vec<Robot>) -> usize {
-    let mut map = Map {
-        size: IVec2::new(101, 103),
-        robots: machines.clone(),
-    };
+pub fn solve_part1(map: &Map) -> usize {
+    let mut map = map.clone();
 
     map.step(100);
 
@@ -153,46 +185,101 @@ pub fn solve_part1(machines: &Vec<Robot>) -> usize {
 }
 
 #[aoc(day14, part2)]
-pub fn solve_part2(machines: &Vec<Robot>) -> usize {
-    let mut map = Map {
-        size: IVec2::new(101, 103),
-        robots: machines.clone(),
-    };
+pub fn solve_part2(map: &Map) -> Option<usize> {
+    let mut map = map.clone();
 
+    // Every robot is back where it started after `width * height` steps, so
+    // if there's no tree by then there never will be.
+    let period = (map.size.x * map.size.y) as usize;
     let mut i = 0;
     while !map.has_long_vertical_span() {
+        if i == period {
+            debug!(period, "no tree within one period");
+            return None;
+        }
         map.step(1);
         i += 1;
+        trace!(frame = i, "stepped");
     }
 
-    println!("{}", map);
+    debug!(frame = i, "found tree:\n{}", map);
 
-    i
+    Some(i)
+}
+
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(500, scale))
+        .map(|_| {
+            format!(
+                "p={},{} v={},{}\n",
+                rng.random_range(0..SIZE.x),
+                rng.random_range(0..SIZE.y),
+                rng.random_range(-99..100),
+                rng.random_range(-99..100)
+            )
+        })
+        .collect()
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        solve_part2(input).map(Answer::from)
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn simulate(input: &Self::Input, _part: u32) -> Option<Box<dyn Simulation + '_>> {
+        Some(Box::new(input.clone()))
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::fixture::Fixture;
+
+    fn robot() -> impl Strategy<Value = Robot> {
+        (0..SIZE.x, 0..SIZE.y, -SIZE.x..SIZE.x, -SIZE.y..SIZE.y).prop_map(|(x, y, dx, dy)| Robot {
+            pos: IVec2::new(x, y),
+            vel: IVec2::new(dx, dy),
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_day14_display_round_trip(robots in prop::collection::vec(robot(), 1..50)) {
+            let input = robots.iter().join("\n");
+            prop_assert_eq!(input_generator(&input).unwrap().robots, robots);
+        }
+    }
 
     #[test]
     fn test_day14_input_generator() {
-        let input = "p=0,4 v=3,-3
-p=6,3 v=-1,-3
-p=10,3 v=-1,2
-p=2,0 v=2,-1
-p=0,0 v=1,3
-p=3,0 v=-2,-2
-p=7,6 v=-1,-3
-p=3,0 v=-1,-2
-p=9,3 v=2,3
-p=7,3 v=-1,2
-p=2,4 v=2,-3
-p=9,5 v=-3,-3";
-        let robots = input_generator(input);
-        let mut map = Map {
-            size: IVec2::new(11, 7),
-            robots,
-        };
+        let input = Fixture::load(14, "example").unwrap().input;
+        let mut map = input_generator(&input).unwrap();
+        assert_eq!(map.size, IVec2::new(11, 7));
 
         assert_eq!(
             map.to_string(),
@@ -219,5 +306,22 @@ p=9,5 v=-3,-3";
         );
 
         assert_eq!(map.safety_factor(), 12);
+
+        // Robots that happen to fit in a small room are still in the real one.
+        assert_eq!(input_generator("p=1,1 v=1,1").unwrap().size, SIZE);
+        assert!(input_generator("size=0,7\np=0,0 v=1,1").is_err());
+    }
+
+    #[test]
+    fn test_day14_parse_error() {
+        let error = input_generator("p=0,4 v=3,-3\np=6,3 -1,-3").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "\"v=\"");
+
+        let error = input_generator("p=0;4 v=3,-3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 4: expected ',' at \";4 v=3,-3\""
+        );
     }
 }
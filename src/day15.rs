@@ -3,12 +3,20 @@ use std::{collections::VecDeque, fmt::Display};
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
 use nom::{
-    character::complete::{newline, one_of},
-    multi::{many1, separated_list1},
-    sequence::separated_pair,
-    IResult, Parser,
+    character::complete::one_of, combinator::map_res, multi::many1, sequence::separated_pair,
+};
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    animate::Simulation,
+    answer::Answer,
+    direction::Direction,
+    generate::{char_grid, count, side},
+    grid::Grid,
+    parse::{blank_line, finish, grid, lines, IResult, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::Solution,
 };
-use vecgrid::Vecgrid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -18,30 +26,28 @@ enum Tile {
     Robot,
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Tile::Empty,
-            '#' => Tile::Wall,
-            'O' => Tile::Box,
-            '@' => Tile::Robot,
-            _ => unreachable!(),
+            '.' => Ok(Tile::Empty),
+            '#' => Ok(Tile::Wall),
+            'O' => Ok(Tile::Box),
+            '@' => Ok(Tile::Robot),
+            _ => Err(c),
         }
     }
 }
 
-impl Display for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Tile::Empty => ".",
-                Tile::Wall => "#",
-                Tile::Box => "O",
-                Tile::Robot => "@",
-            }
-        )
+impl From<Tile> for Cell {
+    fn from(tile: Tile) -> Self {
+        match tile {
+            Tile::Empty => Cell::new('.'),
+            Tile::Wall => Cell::new('#').with_colour(Colour::Grey),
+            Tile::Box => Cell::new('O').with_colour(Colour::Yellow),
+            Tile::Robot => Cell::new('@').with_colour(Colour::Red).highlighted(),
+        }
     }
 }
 
@@ -54,71 +60,32 @@ enum Tile2 {
     Robot,
 }
 
-impl Display for Tile2 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Tile2::Empty => ".",
-                Tile2::Wall => "#",
-                Tile2::BoxLeft => "[",
-                Tile2::BoxRight => "]",
-                Tile2::Robot => "@",
-            }
-        )
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl From<Direction> for IVec2 {
-    fn from(direction: Direction) -> Self {
-        match direction {
-            Direction::Up => IVec2::NEG_Y,
-            Direction::Right => IVec2::X,
-            Direction::Down => IVec2::Y,
-            Direction::Left => IVec2::NEG_X,
+impl Tile2 {
+    fn widen(tile: &Tile) -> [Tile2; 2] {
+        match tile {
+            Tile::Empty => [Tile2::Empty, Tile2::Empty],
+            Tile::Wall => [Tile2::Wall, Tile2::Wall],
+            Tile::Box => [Tile2::BoxLeft, Tile2::BoxRight],
+            Tile::Robot => [Tile2::Robot, Tile2::Empty],
         }
     }
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            '^' => Direction::Up,
-            '>' => Direction::Right,
-            'v' => Direction::Down,
-            '<' => Direction::Left,
-            _ => unreachable!(),
+impl From<Tile2> for Cell {
+    fn from(tile: Tile2) -> Self {
+        match tile {
+            Tile2::Empty => Cell::new('.'),
+            Tile2::Wall => Cell::new('#').with_colour(Colour::Grey),
+            Tile2::BoxLeft => Cell::new('[').with_colour(Colour::Yellow),
+            Tile2::BoxRight => Cell::new(']').with_colour(Colour::Yellow),
+            Tile2::Robot => Cell::new('@').with_colour(Colour::Red).highlighted(),
         }
     }
 }
 
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Direction::Up => "^",
-                Direction::Right => ">",
-                Direction::Down => "v",
-                Direction::Left => "<",
-            }
-        )
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Game {
-    map: Vecgrid<Tile>,
+    map: Grid<Tile>,
     robot_pos: IVec2,
     movements: VecDeque<Direction>,
     last_move: Option<Direction>,
@@ -126,11 +93,11 @@ pub struct Game {
 
 impl Game {
     fn get(&self, pos: IVec2) -> Option<Tile> {
-        self.map.get(pos.y as usize, pos.x as usize).copied()
+        self.map.get(pos).copied()
     }
 
     fn set(&mut self, pos: IVec2, tile: Tile) {
-        self.map.set(pos.y as usize, pos.x as usize, tile).unwrap();
+        self.map[pos] = tile;
     }
 
     fn move_obstacle(&mut self, pos: IVec2, direction: Direction) -> bool {
@@ -146,14 +113,10 @@ impl Game {
                 self.set(new_pos, tile);
                 true
             }
-            Some(Tile::Box) => {
-                if self.move_obstacle(new_pos, direction) {
-                    self.set(pos, Tile::Empty);
-                    self.set(new_pos, tile);
-                    true
-                } else {
-                    false
-                }
+            Some(Tile::Box) if self.move_obstacle(new_pos, direction) => {
+                self.set(pos, Tile::Empty);
+                self.set(new_pos, tile);
+                true
             }
             _ => false,
         }
@@ -183,30 +146,50 @@ impl Game {
 
     fn box_positions(&self) -> Vec<IVec2> {
         self.map
-            .enumerate_column_major()
+            .iter()
             .filter(|(_, &tile)| tile == Tile::Box)
-            .map(|(pos, _)| IVec2::new(pos.0 as i32, pos.1 as i32))
+            .map(|(pos, _)| pos)
             .collect()
     }
+
+    /// The part 2 warehouse, with everything except the robot twice as wide.
+    pub fn widen(&self) -> Game2 {
+        let rows = self
+            .map
+            .rows()
+            .map(|row| row.iter().flat_map(Tile2::widen).collect())
+            .collect();
+
+        Game2 {
+            map: Grid::from_rows(rows).expect("widened rows have equal lengths"),
+            robot_pos: IVec2::new(self.robot_pos.x * 2, self.robot_pos.y),
+            movements: self.movements.clone(),
+            last_move: self.last_move,
+        }
+    }
+}
+
+impl Simulation for Game {
+    fn step(&mut self) -> bool {
+        Game::step(self)
+    }
+}
+
+impl Render for Game {
+    fn render(&self) -> Frame {
+        Frame::new(self.map.map(|&tile| tile.into()))
+    }
 }
 
 impl Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.map.column_len() {
-            for x in 0..self.map.row_len() {
-                write!(f, "{}", self.map.get(y, x).unwrap_or(&Tile::Empty))?;
-            }
-            if y < self.map.column_len() - 1 {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.render())
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Game2 {
-    map: Vecgrid<Tile2>,
+    map: Grid<Tile2>,
     robot_pos: IVec2,
     movements: VecDeque<Direction>,
     last_move: Option<Direction>,
@@ -214,11 +197,11 @@ pub struct Game2 {
 
 impl Game2 {
     fn get(&self, pos: IVec2) -> Option<Tile2> {
-        self.map.get(pos.y as usize, pos.x as usize).copied()
+        self.map.get(pos).copied()
     }
 
     fn set(&mut self, pos: IVec2, tile: Tile2) {
-        self.map.set(pos.y as usize, pos.x as usize, tile).unwrap();
+        self.map[pos] = tile;
     }
 
     fn move_obstacle(&mut self, pos: IVec2, direction: Direction) -> bool {
@@ -236,7 +219,7 @@ impl Game2 {
                 true
             }
             Some(Tile2::BoxLeft | Tile2::BoxRight) => match direction {
-                Direction::Left | Direction::Right => {
+                Direction::W | Direction::E => {
                     if self.move_obstacle(new_pos, direction) {
                         self.set(pos, Tile2::Empty);
                         self.set(new_pos, tile);
@@ -245,7 +228,7 @@ impl Game2 {
                         false
                     }
                 }
-                Direction::Up | Direction::Down => match tile_at_new_pos {
+                _ => match tile_at_new_pos {
                     Some(Tile2::BoxLeft) => {
                         if self.move_obstacle(new_pos, direction)
                             && self.move_obstacle(new_pos + IVec2::X, direction)
@@ -301,83 +284,61 @@ impl Game2 {
 
     fn box_positions(&self) -> Vec<IVec2> {
         self.map
-            .enumerate_column_major()
+            .iter()
             .filter(|(_, &tile)| tile == Tile2::BoxLeft)
-            .map(|(pos, _)| IVec2::new(pos.0 as i32, pos.1 as i32))
+            .map(|(pos, _)| pos)
             .collect()
     }
 }
 
+impl Simulation for Game2 {
+    fn step(&mut self) -> bool {
+        Game2::step(self)
+    }
+}
+
+impl Render for Game2 {
+    fn render(&self) -> Frame {
+        Frame::new(self.map.map(|&tile| tile.into()))
+    }
+}
+
 impl Display for Game2 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.map.column_len() {
-            for x in 0..self.map.row_len() {
-                write!(f, "{}", self.map.get(y, x).unwrap_or(&Tile2::Empty))?;
-            }
-            if y < self.map.column_len() - 1 {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.render())
     }
 }
 
-fn parse_map(input: &str) -> IResult<&str, Vecgrid<Tile>> {
-    let (input, rows) =
-        separated_list1(newline, many1(one_of(".#O@").map(|c| Tile::from(c))))(input)?;
-    Ok((input, Vecgrid::from_rows(rows).unwrap()))
+fn parse_map(input: &str) -> IResult<&str, Grid<Tile>> {
+    grid(input)
 }
 
 fn parse_movements(input: &str) -> IResult<&str, VecDeque<Direction>> {
-    separated_list1(newline, many1(one_of("^>v<").map(|c| Direction::from(c))))(input)
+    lines(many1(map_res(one_of("^>v<"), Direction::try_from)))(input)
         .map(|(input, movements)| (input, VecDeque::from_iter(movements.into_iter().flatten())))
 }
 
-fn parse_map_part2(input: &str) -> IResult<&str, Vecgrid<Tile2>> {
-    let (input, rows) = separated_list1(
-        newline,
-        many1(one_of(".#O@").map(|c| match c {
-            '.' => vec![Tile2::Empty, Tile2::Empty],
-            '#' => vec![Tile2::Wall, Tile2::Wall],
-            'O' => vec![Tile2::BoxLeft, Tile2::BoxRight],
-            '@' => vec![Tile2::Robot, Tile2::Empty],
-            _ => unreachable!(),
-        }))
-        .map(|v| v.into_iter().flatten().collect()),
-    )(input)?;
-    Ok((input, Vecgrid::from_rows(rows).unwrap()))
-}
-
 #[aoc_generator(day15, part1)]
-pub fn input_generator_part1(input: &str) -> Game {
-    separated_pair(parse_map, many1(newline), parse_movements)(input)
-        .map(|(_, (map, movements))| Game {
-            map: map.clone(),
-            robot_pos: map
-                .enumerate_column_major()
-                .find(|(_, &tile)| tile == Tile::Robot)
-                .map(|(pos, _)| IVec2::new(pos.1 as i32, pos.0 as i32))
-                .unwrap(),
-            movements,
-            last_move: None,
-        })
-        .unwrap()
+pub fn input_generator_part1(input: &str) -> Result<Game, ParseError> {
+    let (map, movements) = finish(
+        input,
+        separated_pair(parse_map, blank_line, parse_movements),
+    )?;
+    let robot_pos = map
+        .find(|&tile| tile == Tile::Robot)
+        .ok_or_else(|| ParseError::new(input, input, "a robot `@`"))?;
+
+    Ok(Game {
+        map,
+        robot_pos,
+        movements,
+        last_move: None,
+    })
 }
 
 #[aoc_generator(day15, part2)]
-pub fn input_generator_part2(input: &str) -> Game2 {
-    separated_pair(parse_map_part2, many1(newline), parse_movements)(input)
-        .map(|(_, (map, movements))| Game2 {
-            map: map.clone(),
-            robot_pos: map
-                .enumerate_column_major()
-                .find(|(_, &tile)| tile == Tile2::Robot)
-                .map(|(pos, _)| IVec2::new(pos.1 as i32, pos.0 as i32))
-                .unwrap(),
-            movements,
-            last_move: None,
-        })
-        .unwrap()
+pub fn input_generator_part2(input: &str) -> Result<Game2, ParseError> {
+    input_generator_part1(input).map(|game| game.widen())
 }
 
 #[aoc(day15, part1)]
@@ -387,7 +348,7 @@ pub fn solve_part1(game: &Game) -> i32 {
 
     game.box_positions()
         .iter()
-        .map(|pos| 100 * pos.x + pos.y)
+        .map(|pos| 100 * pos.y + pos.x)
         .sum()
 }
 
@@ -398,27 +359,153 @@ pub fn solve_part2(game: &Game2) -> i32 {
 
     game.box_positions()
         .iter()
-        .map(|pos| 100 * pos.x + pos.y)
+        .map(|pos| 100 * pos.y + pos.x)
         .sum()
 }
 
+/// A random walled warehouse and moves, with boxes and walls about as dense
+/// as the real one.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(50, scale).max(3);
+    let robot = (rng.random_range(1..side - 1), rng.random_range(1..side - 1));
+    let map = char_grid(side, side, |x, y| {
+        if x == 0 || y == 0 || x == side - 1 || y == side - 1 {
+            '#'
+        } else if (x, y) == robot {
+            '@'
+        } else {
+            match rng.random_range(0..100) {
+                0..8 => '#',
+                8..32 => 'O',
+                _ => '.',
+            }
+        }
+    });
+
+    let moves: Vec<char> = (0..count(20000, scale))
+        .map(|_| ['<', '>', '^', 'v'][rng.random_range(0..4)])
+        .collect();
+    let moves: String = moves
+        .chunks(1000)
+        .map(|line| line.iter().collect::<String>() + "\n")
+        .collect();
+
+    map + "\n" + &moves
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Game;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator_part1(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(&input.widen()).into())
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn simulate(input: &Self::Input, part: u32) -> Option<Box<dyn Simulation + '_>> {
+        if part == 1 {
+            Some(Box::new(input.clone()))
+        } else {
+            Some(Box::new(input.widen()))
+        }
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::fixture::Fixture;
+
+    /// A walled warehouse with one robot, and a list of moves for it, as the
+    /// two sections of an input.
+    fn warehouse() -> impl Strategy<Value = (String, String)> {
+        (3..12usize, 3..12usize)
+            .prop_flat_map(|(width, height)| {
+                (
+                    Just((width, height)),
+                    prop::collection::vec(
+                        prop::sample::select(vec!['.', '.', '#', 'O']),
+                        width * height,
+                    ),
+                    0..width * height,
+                    "[<>^v]{1,200}",
+                )
+            })
+            .prop_map(|((width, height), mut cells, robot, moves)| {
+                cells[robot] = '@';
+                let map = (0..height + 2)
+                    .map(|y| {
+                        (0..width + 2)
+                            .map(|x| {
+                                if x == 0 || y == 0 || x == width + 1 || y == height + 1 {
+                                    '#'
+                                } else {
+                                    cells[(y - 1) * width + x - 1]
+                                }
+                            })
+                            .collect::<String>()
+                    })
+                    .join("\n");
+                (map, moves)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_day15_display_round_trip((map, moves) in warehouse()) {
+            let game = input_generator_part1(&format!("{}\n\n{}", map, moves)).unwrap();
+            prop_assert_eq!(game.to_string(), map);
+
+            let reparsed = input_generator_part1(&format!("{}\n\n{}", game, moves)).unwrap();
+            prop_assert_eq!(reparsed.map, game.map);
+            prop_assert_eq!(reparsed.movements, game.movements);
+        }
+
+        #[test]
+        fn test_day15_run_keeps_boxes((map, moves) in warehouse()) {
+            let input = format!("{}\n\n{}", map, moves);
+
+            let mut game = input_generator_part1(&input).unwrap();
+            let boxes = game.box_positions().len();
+            game.run();
+            prop_assert_eq!(game.box_positions().len(), boxes);
+            prop_assert_eq!(game.get(game.robot_pos), Some(Tile::Robot));
+
+            let mut game = input_generator_part2(&input).unwrap();
+            game.run();
+            prop_assert_eq!(game.box_positions().len(), boxes);
+            for pos in game.box_positions() {
+                prop_assert_eq!(game.get(pos + IVec2::X), Some(Tile2::BoxRight));
+            }
+            prop_assert_eq!(game.get(game.robot_pos), Some(Tile2::Robot));
+        }
+    }
 
     #[test]
     fn test_day15_input_generator() {
-        let input = "########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
-
-<^^>>>vv<v>>v<<";
-        let game = input_generator_part1(input);
+        let input = Fixture::load(15, "small").unwrap().input;
+        let game = input_generator_part1(&input).unwrap();
         assert_eq!(
             game.to_string(),
             "########
@@ -429,35 +516,18 @@ mod test {
 #...O..#
 #......#
 ########"
-        )
+        );
+
+        let error = input_generator_part1("#####\n#@.x#\n#####\n\n<>").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.snippet, "x#\n");
     }
 
     #[test]
     fn test_day15_part1() {
-        let input = "##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
-
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        let mut game = input_generator_part1(input);
+        let input = Fixture::load(15, "example").unwrap().input;
+        let mut game = input_generator_part1(&input).unwrap();
         game.run();
-        println!("{}", game);
         assert_eq!(
             game.to_string(),
             "##########
@@ -475,40 +545,52 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
     #[test]
     fn test_day15_part2_input_generator() {
-        let input = "#######
-#...#.#
-#.....#
-#..OO@#
-#..O..#
-#.....#
-#######
-
-<vv<<^^<<^^";
-        let game = input_generator_part2(input);
-        println!("{}", game);
+        let input = Fixture::load(15, "wide").unwrap().input;
+        let game = input_generator_part2(&input).unwrap();
+        assert_eq!(
+            game.to_string(),
+            "##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############"
+        );
+        assert_eq!(game.robot_pos, IVec2::new(10, 3));
     }
 
     #[test]
     fn test_day15_part2_step() {
-        let input = "#######
-#...#.#
-#.....#
-#..OO@#
-#..O..#
-#.....#
-#######
+        let input = Fixture::load(15, "wide").unwrap().input;
+        let mut game = input_generator_part2(&input).unwrap();
 
-<vv<<^^<<^^";
-        let mut game = input_generator_part2(input);
+        assert!(game.step());
+        assert_eq!(
+            game.to_string(),
+            "##############
+##......##..##
+##..........##
+##...[][]@..##
+##....[]....##
+##..........##
+##############"
+        );
 
         let n = game.movements.len();
-        for i in 0..=n {
-            println!("Second {}", i);
-            println!("{}", game);
-            game.step();
-            if i < n {
-                println!();
-            }
+        for _ in 0..n {
+            assert!(game.step());
         }
+        assert!(!game.step());
+        assert_eq!(
+            game.to_string(),
+            "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############"
+        );
     }
 }
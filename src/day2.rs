@@ -1,40 +1,216 @@
+use std::ops::RangeInclusive;
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{
-    character::complete::{i32, newline, space1},
-    multi::separated_list0,
+use itertools::Itertools;
+use nom::character::complete::i32;
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    answer::Answer,
+    generate::count,
+    parse::{finish, lines, spaced, ParseError},
+    solution::{Part, Solution},
 };
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<Vec<i32>> {
-    separated_list0(
-        newline::<&str, nom::error::Error<&str>>,
-        separated_list0(space1, i32),
-    )(input)
-    .map(|(_, result)| result)
-    .unwrap()
+pub fn input_generator(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    finish(input, lines(spaced(i32)))
 }
 
-#[aoc(day2, part1)]
-pub fn solve_part1(reports: &Vec<Vec<i32>>) -> usize {
+/// Which way a report's levels must change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    /// Either way, as long as every change goes the way the first does.
+    Consistent,
+    /// Each change can go either way.
+    Any,
+}
+
+const TRENDS: [Trend; 4] = [
+    Trend::Increasing,
+    Trend::Decreasing,
+    Trend::Consistent,
+    Trend::Any,
+];
+
+/// Whether a report with fewer than two levels, and so no changes, is safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortReport {
+    Safe,
+    Unsafe,
+}
+
+/// The rules a report has to follow to be safe. The default is the puzzle's:
+/// every change is by 1 to 3 levels, all the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// How far a level may change from the one before, ignoring plateaus.
+    pub steps: RangeInclusive<u32>,
+    pub trend: Trend,
+    /// Whether a level may equal the one before. Plateaus don't count
+    /// towards the trend.
+    pub plateaus: bool,
+    pub short: ShortReport,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            steps: 1..=3,
+            trend: Trend::Consistent,
+            plateaus: false,
+            short: ShortReport::Safe,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// The trend the changes after `change` must follow, or `None` if
+    /// `change` breaks the rules when the changes before it set `trend`.
+    fn follow(&self, trend: Trend, change: i32) -> Option<Trend> {
+        if change == 0 {
+            return self.plateaus.then_some(trend);
+        }
+        if !self.steps.contains(&change.unsigned_abs()) {
+            return None;
+        }
+        match trend {
+            Trend::Increasing => (change > 0).then_some(trend),
+            Trend::Decreasing => (change < 0).then_some(trend),
+            Trend::Consistent if change > 0 => Some(Trend::Increasing),
+            Trend::Consistent => Some(Trend::Decreasing),
+            Trend::Any => Some(trend),
+        }
+    }
+
+    /// The index of the first level whose change to the next one breaks the
+    /// rules, if any does.
+    pub fn first_unsafe_change(&self, report: &[i32]) -> Option<usize> {
+        let mut trend = self.trend;
+        report
+            .windows(2)
+            .position(|w| match self.follow(trend, w[1] - w[0]) {
+                Some(next) => {
+                    trend = next;
+                    false
+                }
+                None => true,
+            })
+    }
+
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        if report.len() < 2 {
+            return self.short == ShortReport::Safe;
+        }
+        self.first_unsafe_change(report).is_none()
+    }
+
+    /// The fewest levels to remove from `report` to make it safe, as their
+    /// indices in order, or `None` if it takes more than `k`.
+    ///
+    /// Works through the levels keeping, for each level and trend, the
+    /// cheapest safe run of kept levels that ends there. At most `k` levels
+    /// are skipped, so each run continues from one of the `k + 1` before it,
+    /// and the whole takes O(nk) time.
+    pub fn dampen(&self, report: &[i32], k: usize) -> Option<Vec<usize>> {
+        let n = report.len();
+        if n < 2 {
+            return self.is_safe(report).then(Vec::new);
+        }
+
+        // For each level and the trend after it, the fewest levels before it
+        // removed by a safe run ending there, and the level and trend the run
+        // came from, if it didn't start there.
+        type Run = (usize, Option<(usize, Trend)>);
+        let mut runs: Vec<[Option<Run>; 4]> = vec![[None; 4]; n];
+        for i in 0..n {
+            if i <= k {
+                runs[i][self.trend as usize] = Some((i, None));
+            }
+            for j in i.saturating_sub(k + 1)..i {
+                for (trend, run) in TRENDS.into_iter().zip(runs[j]) {
+                    let Some((removed, _)) = run else {
+                        continue;
+                    };
+                    let removed = removed + i - j - 1;
+                    let Some(next) = self.follow(trend, report[i] - report[j]) else {
+                        continue;
+                    };
+                    let best = &mut runs[i][next as usize];
+                    if removed <= k && best.is_none_or(|(fewest, _)| removed < fewest) {
+                        *best = Some((removed, Some((j, trend))));
+                    }
+                }
+            }
+        }
+
+        // A run of one level leaves a short report, so is only safe if those
+        // are.
+        let (mut i, mut trend, _) = (0..n)
+            .flat_map(|i| TRENDS.into_iter().map(move |trend| (i, trend)))
+            .filter_map(|(i, trend)| {
+                let (removed, from) = runs[i][trend as usize]?;
+                let removed = removed + n - 1 - i;
+                (removed <= k && (from.is_some() || self.short == ShortReport::Safe))
+                    .then_some((i, trend, removed))
+            })
+            .min_by_key(|(_, _, removed)| *removed)?;
+
+        let mut kept = vec![false; n];
+        loop {
+            kept[i] = true;
+            match runs[i][trend as usize].and_then(|(_, from)| from) {
+                Some(from) => (i, trend) = from,
+                None => break,
+            }
+        }
+        Some((0..n).filter(|i| !kept[*i]).collect())
+    }
+
+    /// Whether `report` is safe, or can be made safe by removing one level.
+    pub fn is_safe_dampened(&self, report: &[i32]) -> bool {
+        self.dampen(report, 1).is_some()
+    }
+}
+
+fn without(report: &[i32], i: usize) -> Vec<i32> {
+    let mut report = report.to_vec();
+    report.remove(i);
+    report
+}
+
+pub fn solve_part1_with(reports: &[Vec<i32>], policy: &SafetyPolicy) -> usize {
     reports
         .iter()
-        .filter(|report| is_report_safe(report))
+        .filter(|report| policy.is_safe(report))
         .count()
 }
 
-#[aoc(day2, part2)]
-pub fn solve_part2(reports: &Vec<Vec<i32>>) -> usize {
+pub fn solve_part2_with(reports: &[Vec<i32>], policy: &SafetyPolicy) -> usize {
+    count_dampened(reports, policy, 1)
+}
+
+/// How many reports are safe once at most `k` levels are removed from each.
+pub fn count_dampened(reports: &[Vec<i32>], policy: &SafetyPolicy, k: usize) -> usize {
+    reports
+        .iter()
+        .filter(|report| policy.dampen(report, k).is_some())
+        .count()
+}
+
+/// Tries removing each level in turn, to check [`solve_part2_with`] against.
+pub fn solve_part2_reference_with(reports: &[Vec<i32>], policy: &SafetyPolicy) -> usize {
     reports
         .iter()
         .filter(|&report| {
-            if is_report_safe(report) {
+            if policy.is_safe(report) {
                 return true;
             }
 
             for i in 0..report.len() {
-                let mut report_without_i = report.clone();
-                report_without_i.remove(i);
-                if is_report_safe(&report_without_i) {
+                if policy.is_safe(&without(report, i)) {
                     return true;
                 }
             }
@@ -43,11 +219,218 @@ pub fn solve_part2(reports: &Vec<Vec<i32>>) -> usize {
         .count()
 }
 
-fn is_report_safe(report: &Vec<i32>) -> bool {
-    let pair_diffs = report.windows(2).map(|w| w[0] - w[1]).collect::<Vec<i32>>();
-    let signum = pair_diffs[0].signum();
-    pair_diffs.iter().all(|diff| {
-        let dist = diff.abs();
-        diff.signum() == signum && dist >= 1 && dist <= 3
-    })
+#[aoc(day2, part1)]
+pub fn solve_part1(reports: &[Vec<i32>]) -> usize {
+    solve_part1_with(reports, &SafetyPolicy::default())
+}
+
+#[aoc(day2, part2)]
+pub fn solve_part2(reports: &[Vec<i32>]) -> usize {
+    solve_part2_with(reports, &SafetyPolicy::default())
+}
+
+#[aoc(day2, part2, reference)]
+pub fn solve_part2_reference(reports: &[Vec<i32>]) -> usize {
+    solve_part2_reference_with(reports, &SafetyPolicy::default())
+}
+
+/// Random reports that mostly change by safe steps, with the odd bad one.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(1000, scale))
+        .map(|_| {
+            let increasing = rng.random_bool(0.5);
+            let mut level: i32 = if increasing {
+                rng.random_range(1..=60)
+            } else {
+                rng.random_range(40..=99)
+            };
+            let mut levels = vec![level];
+            for _ in 1..rng.random_range(5..=8) {
+                let step = if rng.random_bool(0.9) {
+                    rng.random_range(1..=3)
+                } else {
+                    rng.random_range(-3..=6)
+                };
+                level += if increasing { step } else { -step };
+                levels.push(level);
+            }
+            levels.iter().join(" ") + "\n"
+        })
+        .collect()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+
+    fn reference(part: u32) -> Option<Part<Self::Input>> {
+        match part {
+            2 => Some(|input| Some(solve_part2_reference(input).into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_safety_policy() {
+        let policy = SafetyPolicy::default();
+        assert!(policy.is_safe(&[7, 6, 4, 2, 1]));
+        assert!(!policy.is_safe(&[1, 3, 2, 4, 5]));
+        assert!(policy.is_safe_dampened(&[1, 3, 2, 4, 5]));
+        assert!(!policy.is_safe_dampened(&[1, 2, 7, 8, 9]));
+
+        // Short reports used to panic.
+        assert!(policy.is_safe(&[5]));
+        assert!(policy.is_safe_dampened(&[5, 9]));
+        let strict = SafetyPolicy {
+            short: ShortReport::Unsafe,
+            ..SafetyPolicy::default()
+        };
+        assert!(!strict.is_safe(&[5]));
+        assert!(strict.is_safe(&[5, 6]));
+        assert!(!strict.is_safe_dampened(&[5, 9]));
+
+        let rising = SafetyPolicy {
+            trend: Trend::Increasing,
+            ..SafetyPolicy::default()
+        };
+        assert!(!rising.is_safe(&[7, 6, 4, 2, 1]));
+        assert!(rising.is_safe(&[1, 3, 6, 7, 9]));
+
+        let flat = SafetyPolicy {
+            plateaus: true,
+            ..SafetyPolicy::default()
+        };
+        assert!(flat.is_safe(&[8, 6, 4, 4, 1]));
+        // Only removing the first level, which set the trend, fixes this.
+        assert!(flat.is_safe_dampened(&[1, 2, 2, 2, 2, 1, 0]));
+
+        let wide = SafetyPolicy {
+            steps: 1..=5,
+            trend: Trend::Any,
+            ..SafetyPolicy::default()
+        };
+        assert!(wide.is_safe(&[1, 6, 2, 7]));
+        assert!(!wide.is_safe(&[1, 7]));
+    }
+
+    #[test]
+    fn test_dampen() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(policy.dampen(&[7, 6, 4, 2, 1], 0), Some(vec![]));
+        assert_eq!(policy.dampen(&[1, 3, 2, 4, 5], 1), Some(vec![2]));
+        assert_eq!(policy.dampen(&[8, 6, 4, 4, 1], 1), Some(vec![3]));
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 1), None);
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 2), Some(vec![0, 1]));
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 3), Some(vec![0, 1]));
+        assert_eq!(
+            policy.dampen(&[1, 9, 2, 9, 3, 9, 4], 3),
+            Some(vec![1, 3, 5])
+        );
+        assert_eq!(policy.dampen(&[5, 1], 0), None);
+        assert_eq!(policy.dampen(&[5, 1], 1), Some(vec![1]));
+        assert_eq!(policy.dampen(&[5], 0), Some(vec![]));
+
+        let strict = SafetyPolicy {
+            short: ShortReport::Unsafe,
+            ..SafetyPolicy::default()
+        };
+        assert_eq!(strict.dampen(&[5, 1], 5), None);
+        assert_eq!(strict.dampen(&[5, 1, 2], 5), Some(vec![0]));
+
+        let reports = input_generator("7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n").unwrap();
+        assert_eq!(count_dampened(&reports, &policy, 0), 1);
+        assert_eq!(count_dampened(&reports, &policy, 1), 2);
+        assert_eq!(count_dampened(&reports, &policy, 2), 4);
+    }
+
+    /// The fewest levels to remove to make `report` safe, by trying every
+    /// way of removing up to `k`.
+    fn fewest_removals(policy: &SafetyPolicy, report: &[i32], k: usize) -> Option<usize> {
+        (0..=k.min(report.len())).find(|&n| {
+            (0..report.len()).combinations(n).any(|removed| {
+                let kept = (0..report.len())
+                    .filter(|i| !removed.contains(i))
+                    .map(|i| report[i])
+                    .collect_vec();
+                policy.is_safe(&kept)
+            })
+        })
+    }
+
+    fn policy() -> impl Strategy<Value = SafetyPolicy> {
+        let trend = prop_oneof![
+            Just(Trend::Increasing),
+            Just(Trend::Decreasing),
+            Just(Trend::Consistent),
+            Just(Trend::Any),
+        ];
+        let short = prop_oneof![Just(ShortReport::Safe), Just(ShortReport::Unsafe)];
+        (0..3u32, 0..4u32, trend, any::<bool>(), short).prop_map(
+            |(min, extra, trend, plateaus, short)| SafetyPolicy {
+                steps: min..=min + extra,
+                trend,
+                plateaus,
+                short,
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_day2_dampen_removes_fewest(
+            policy in policy(),
+            report in prop::collection::vec(0..10i32, 0..9),
+            k in 0..4usize,
+        ) {
+            let removed = policy.dampen(&report, k);
+            prop_assert_eq!(
+                removed.as_ref().map(Vec::len),
+                fewest_removals(&policy, &report, k)
+            );
+            if let Some(removed) = removed {
+                let kept = (0..report.len())
+                    .filter(|i| !removed.contains(i))
+                    .map(|i| report[i])
+                    .collect_vec();
+                prop_assert!(policy.is_safe(&kept), "{:?} without {:?}", report, removed);
+            }
+        }
+
+        #[test]
+        fn test_day2_dampener_matches_reference(
+            policy in policy(),
+            reports in prop::collection::vec(prop::collection::vec(0..10i32, 1..8), 1..20),
+        ) {
+            prop_assert_eq!(
+                solve_part2_with(&reports, &policy),
+                solve_part2_reference_with(&reports, &policy)
+            );
+        }
+    }
 }
@@ -1,40 +1,47 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{anychar, u32},
-    combinator::map,
+    combinator::{map, rest},
     multi::{many0, many_till},
-    sequence::{delimited, separated_pair},
+    sequence::{delimited, separated_pair, terminated},
     Parser,
 };
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt};
 
-fn parse_mul(input: &str) -> nom::IResult<&str, (u32, u32)> {
+use crate::{
+    answer::Answer,
+    generate::{count, number},
+    parse::{finish, tag, IResult, ParseError},
+    solution::Solution,
+};
+
+fn parse_mul(input: &str) -> IResult<&str, (u32, u32)> {
     delimited(tag("mul("), separated_pair(u32, tag(","), u32), tag(")"))(input)
 }
 
-fn parse_all_mul(input: &str) -> nom::IResult<&str, Vec<(u32, u32)>> {
+fn parse_all_mul(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
     many0(many_till(anychar, parse_mul).map(|(_, mul)| mul))(input)
 }
 
 #[aoc_generator(day3, part1)]
-pub fn input_generator_part1(input: &str) -> Vec<(u32, u32)> {
-    parse_all_mul(input).unwrap().1
+pub fn input_generator_part1(input: &str) -> Result<Vec<(u32, u32)>, ParseError> {
+    finish(input, terminated(parse_all_mul, rest))
 }
 
 #[aoc(day3, part1)]
-pub fn solve_part1(input: &Vec<(u32, u32)>) -> u32 {
+pub fn solve_part1(input: &[(u32, u32)]) -> u32 {
     input.iter().map(|(x, y)| x * y).sum()
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Instruction {
+pub enum Instruction {
     Mul(u32, u32),
     Do,
     Dont,
 }
 
-fn parse_all_mul_with_conds(input: &str) -> nom::IResult<&str, Vec<Instruction>> {
+fn parse_all_mul_with_conds(input: &str) -> IResult<&str, Vec<Instruction>> {
     many0(
         many_till(
             anychar,
@@ -48,10 +55,13 @@ fn parse_all_mul_with_conds(input: &str) -> nom::IResult<&str, Vec<Instruction>>
     )(input)
 }
 
-#[aoc(day3, part2)]
-pub fn solve_part2(input: &str) -> u32 {
-    let (_, insts) = parse_all_mul_with_conds(input).unwrap();
+#[aoc_generator(day3, part2)]
+pub fn input_generator_part2(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    finish(input, terminated(parse_all_mul_with_conds, rest))
+}
 
+#[aoc(day3, part2)]
+pub fn solve_part2(insts: &[Instruction]) -> u32 {
     let mut sum = 0;
     let mut do_mul = true;
     for inst in insts {
@@ -69,9 +79,72 @@ pub fn solve_part2(input: &str) -> u32 {
     sum
 }
 
+/// Fragments that aren't instructions, some of which almost are.
+const NOISE: &[&str] = &[
+    "who()", "what()", "why()", "when()", "where()", "how()", "from()", "select()", "mul(", "mul[",
+    "mul ", "do(", "don't", "(", ")", ",", "'", "~", "^", "%", "$", "@", "#", "!", "?", "<", ">",
+    "[", "]", "{", "}", "*", "+", "-", "/", ":", ";", " ",
+];
+
+/// Random corrupted memory, with about as many instructions per line as the
+/// real input.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(6, scale))
+        .map(|_| {
+            let mut line = String::new();
+            while line.len() < 3000 {
+                match rng.random_range(0..100) {
+                    0..15 => line += &format!("mul({},{})", number(rng, 1, 3), number(rng, 1, 3)),
+                    15 => line += "do()",
+                    16 => line += "don't()",
+                    17..22 => {
+                        let end = *['!', ']', ' ', '*'].choose(rng).unwrap();
+                        line += &format!("mul({},{}{}", number(rng, 1, 3), number(rng, 1, 3), end)
+                    }
+                    _ => line += NOISE.choose(rng).unwrap(),
+                }
+            }
+            line + "\n"
+        })
+        .collect()
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator_part2(input)
+    }
+
+    /// Part 1 ignores `do()` and `don't()`, so it only needs the muls.
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        let muls: Vec<_> = input
+            .iter()
+            .filter_map(|inst| match inst {
+                Instruction::Mul(x, y) => Some((*x, *y)),
+                _ => None,
+            })
+            .collect();
+        Some(solve_part1(&muls).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::{Error, Expected};
 
     #[test]
     fn test_parse_mul() {
@@ -79,10 +152,10 @@ mod tests {
         assert_eq!(parse_mul("mul(1,2)abc"), Ok(("abc", (1, 2))));
         assert_eq!(
             parse_mul("abc"),
-            Err(nom::Err::Error(nom::error::Error::new(
-                "abc",
-                nom::error::ErrorKind::Tag
-            )))
+            Err(nom::Err::Error(Error {
+                input: "abc",
+                expected: Expected::Literal("mul(")
+            }))
         );
     }
 
@@ -109,7 +182,7 @@ mod tests {
 
     #[test]
     fn test_solve_part2() {
-        assert_eq!(solve_part2("don't()"), 0);
+        assert_eq!(solve_part2(&input_generator_part2("don't()").unwrap()), 0);
     }
 
     #[test]
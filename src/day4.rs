@@ -1,56 +1,24 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use rand::{rngs::StdRng, RngExt};
 
-type Grid = Vec<Vec<char>>;
+use crate::{
+    answer::Answer,
+    direction::Direction,
+    generate::{char_grid, side},
+    grid::Grid,
+    parse::{finish, grid, ParseError},
+    solution::Solution,
+};
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Grid {
-    input.lines().map(|line| line.chars().collect()).collect()
+pub fn input_generator(input: &str) -> Result<Grid<char>, ParseError> {
+    finish(input, grid)
 }
 
-#[derive(Debug, EnumIter, Clone, Copy)]
-enum Direction {
-    N,
-    NE,
-    E,
-    SE,
-    S,
-    SW,
-    W,
-    NW,
-}
-
-impl From<Direction> for IVec2 {
-    fn from(dir: Direction) -> Self {
-        match dir {
-            Direction::N => IVec2::new(0, -1),
-            Direction::NE => IVec2::new(1, -1),
-            Direction::E => IVec2::new(1, 0),
-            Direction::SE => IVec2::new(1, 1),
-            Direction::S => IVec2::new(0, 1),
-            Direction::SW => IVec2::new(-1, 1),
-            Direction::W => IVec2::new(-1, 0),
-            Direction::NW => IVec2::new(-1, -1),
-        }
-    }
-}
-
-fn get_pos(grid: &Grid, pos: IVec2) -> Option<char> {
-    if pos.y < 0
-        || pos.y >= grid.len() as i32
-        || pos.x < 0
-        || pos.x >= grid[pos.y as usize].len() as i32
-    {
-        return None;
-    }
-    grid.get(pos.y as usize)?.get(pos.x as usize).copied()
-}
-
-fn matches_in_direction(grid: &Grid, pos: IVec2, dir: Direction, s: &str) -> bool {
+fn matches_in_direction(grid: &Grid<char>, pos: IVec2, dir: Direction, s: &str) -> bool {
     for (i, c) in s.chars().enumerate() {
-        if get_pos(grid, pos + (IVec2::from(dir) * i as i32)) != Some(c) {
+        if grid.get(pos + (IVec2::from(dir) * i as i32)) != Some(&c) {
             return false;
         }
     }
@@ -58,17 +26,14 @@ fn matches_in_direction(grid: &Grid, pos: IVec2, dir: Direction, s: &str) -> boo
 }
 
 #[aoc(day4, part1)]
-pub fn solve_part1(grid: &Vec<Vec<char>>) -> u32 {
+pub fn solve_part1(grid: &Grid<char>) -> u32 {
     let mut count: u32 = 0;
     let test_str = "XMAS";
-    for y in 0..grid.len() as i32 {
-        for x in 0..grid[y as usize].len() as i32 {
-            let pos = IVec2::new(x, y);
-            if matches!(get_pos(grid, pos), Some('X')) {
-                for dir in Direction::iter() {
-                    if matches_in_direction(grid, pos, dir, test_str) {
-                        count += 1;
-                    }
+    for (pos, &c) in grid.iter() {
+        if c == 'X' {
+            for dir in Direction::ALL {
+                if matches_in_direction(grid, pos, dir, test_str) {
+                    count += 1;
                 }
             }
         }
@@ -78,39 +43,67 @@ pub fn solve_part1(grid: &Vec<Vec<char>>) -> u32 {
 }
 
 #[aoc(day4, part2)]
-pub fn solve_part2(grid: &Vec<Vec<char>>) -> u32 {
+pub fn solve_part2(grid: &Grid<char>) -> u32 {
     let test_str = "MAS";
     let mut count: u32 = 0;
-    for y in 0..grid.len() as i32 {
-        for x in 0..grid[y as usize].len() as i32 {
-            let pos = IVec2::new(x, y);
-            if matches!(get_pos(grid, pos), Some('A'))
-                && (matches_in_direction(
-                    grid,
-                    pos + IVec2::from(Direction::NW),
-                    Direction::SE,
-                    test_str,
-                ) || matches_in_direction(
-                    grid,
-                    pos + IVec2::from(Direction::SE),
-                    Direction::NW,
-                    test_str,
-                ))
-                && (matches_in_direction(
-                    grid,
-                    pos + IVec2::from(Direction::NE),
-                    Direction::SW,
-                    test_str,
-                ) || matches_in_direction(
-                    grid,
-                    pos + IVec2::from(Direction::SW),
-                    Direction::NE,
-                    test_str,
-                ))
-            {
-                count += 1;
-            }
+    for (pos, &c) in grid.iter() {
+        if c == 'A'
+            && (matches_in_direction(
+                grid,
+                pos + IVec2::from(Direction::NW),
+                Direction::SE,
+                test_str,
+            ) || matches_in_direction(
+                grid,
+                pos + IVec2::from(Direction::SE),
+                Direction::NW,
+                test_str,
+            ))
+            && (matches_in_direction(
+                grid,
+                pos + IVec2::from(Direction::NE),
+                Direction::SW,
+                test_str,
+            ) || matches_in_direction(
+                grid,
+                pos + IVec2::from(Direction::SW),
+                Direction::NE,
+                test_str,
+            ))
+        {
+            count += 1;
         }
     }
     count
 }
+
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(140, scale);
+    char_grid(side, side, |_, _| {
+        ['X', 'M', 'A', 'S'][rng.random_range(0..4)]
+    })
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
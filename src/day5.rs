@@ -3,9 +3,21 @@ use std::collections::{BTreeSet, HashMap};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::{
-    character::complete::{char, newline, u8},
+    character::complete::{char, u8},
     multi::separated_list1,
-    sequence::{separated_pair, tuple},
+    sequence::separated_pair,
+};
+use rand::{
+    rngs::StdRng,
+    seq::{index, SliceRandom},
+    RngExt,
+};
+
+use crate::{
+    answer::Answer,
+    generate::count,
+    parse::{blank_line, finish, lines, ParseError},
+    solution::Solution,
 };
 
 #[derive(Debug)]
@@ -15,22 +27,20 @@ pub struct SafetyManual {
 }
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> SafetyManual {
-    let (ordering_rules, updates) = separated_pair(
-        separated_list1(
-            newline::<&str, nom::error::Error<&str>>,
-            separated_pair(u8, char('|'), u8),
+pub fn input_generator(input: &str) -> Result<SafetyManual, ParseError> {
+    let (ordering_rules, updates) = finish(
+        input,
+        separated_pair(
+            lines(separated_pair(u8, char('|'), u8)),
+            blank_line,
+            lines(separated_list1(char(','), u8)),
         ),
-        tuple((newline, newline)),
-        separated_list1(newline, separated_list1(char(','), u8)),
-    )(input)
-    .unwrap()
-    .1;
-
-    SafetyManual {
-        ordering_rules: BTreeSet::from_iter(ordering_rules.into_iter()),
+    )?;
+
+    Ok(SafetyManual {
+        ordering_rules: BTreeSet::from_iter(ordering_rules),
         updates,
-    }
+    })
 }
 
 #[aoc(day5, part1)]
@@ -72,12 +82,59 @@ pub fn solve_part2(manual: &SafetyManual) -> u32 {
                 }
             }
 
-            counts
-                .iter()
-                .find(|(&_, &count)| count == 0)
-                .unwrap()
-                .0
-                .clone() as u32
+            *counts.iter().find(|(&_, &count)| count == 0).unwrap().0 as u32
         })
         .sum()
 }
+
+/// Random rules and updates. The pages follow a hidden order with a rule for
+/// every pair, so any update can be put right; about half already are.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let mut pages: Vec<u8> = (10..100).collect();
+    pages.shuffle(rng);
+    pages.truncate(49);
+
+    let mut rules: Vec<String> = pages
+        .iter()
+        .tuple_combinations()
+        .map(|(x, y)| format!("{}|{}\n", x, y))
+        .collect();
+    rules.shuffle(rng);
+
+    let updates: String = (0..count(200, scale))
+        .map(|_| {
+            let len = 2 * rng.random_range(2..=11) + 1;
+            let mut indices = index::sample(rng, pages.len(), len).into_vec();
+            if rng.random_bool(0.5) {
+                indices.sort();
+            }
+            indices.iter().map(|&i| pages[i]).join(",") + "\n"
+        })
+        .collect();
+
+    rules.concat() + "\n" + &updates
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input = SafetyManual;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
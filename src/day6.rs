@@ -2,128 +2,280 @@ use std::collections::{HashMap, HashSet};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
-use nom::{
-    character::complete::{newline, one_of},
-    multi::{many1, separated_list1},
+use nom::character::complete::one_of;
+use rand::{rngs::StdRng, RngExt};
+use tracing::debug;
+
+use crate::{
+    animate::Simulation,
+    answer::Answer,
+    direction::Direction,
+    generate::{char_grid, side},
+    grid::Grid,
+    parse::{finish, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::{Part, Solution},
 };
 
 #[derive(Debug)]
 pub struct Map {
-    size: IVec2,
-    obstructions: HashSet<IVec2>,
+    obstructions: Grid<bool>,
     guard: IVec2,
 }
 
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Map {
-    let raw_map = separated_list1(
-        newline::<&str, nom::error::Error<&str>>,
-        many1(one_of("^#.")),
-    )(input)
-    .unwrap()
-    .1;
-
-    let height = raw_map.len();
-    let width = raw_map[0].len();
-
-    let mut obstructions = HashSet::new();
-    let mut guard = IVec2::ZERO;
-
-    for y in 0..height {
-        for x in 0..width {
-            if raw_map[y][x] == '#' {
-                obstructions.insert(IVec2::new(x as i32, y as i32));
-            }
-            if raw_map[y][x] == '^' {
-                guard = IVec2::new(x as i32, y as i32);
-            }
-        }
-    }
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    let raw_map = finish(input, Grid::parse(one_of("^#.")))?;
+    let guard = raw_map
+        .find(|&c| c == '^')
+        .ok_or_else(|| ParseError::new(input, input, "a guard `^`"))?;
 
-    Map {
-        size: IVec2::new(width as i32, height as i32),
-        obstructions,
+    Ok(Map {
+        obstructions: raw_map.map(|&c| c == '#'),
         guard,
+    })
+}
+
+/// Obstructions and the guard, along with the guard's path unless it loops.
+impl Render for Map {
+    fn render(&self) -> Frame {
+        let path = get_visited(self).unwrap_or_default();
+        Frame::new(self.obstructions.map_with_position(|pos, &obstructed| {
+            if pos == self.guard {
+                Cell::new('^').with_colour(Colour::Red).highlighted()
+            } else if obstructed {
+                Cell::new('#').with_colour(Colour::Grey)
+            } else if path.contains_key(&pos) {
+                Cell::new('X').with_colour(Colour::Cyan)
+            } else {
+                Cell::new('.')
+            }
+        }))
     }
 }
 
-fn in_map(map: &Map, position: IVec2) -> bool {
-    position.x >= 0 && position.x < map.size.x && position.y >= 0 && position.y < map.size.y
+/// The guard's patrol, one move or turn at a time.
+pub struct Patrol<'a> {
+    map: &'a Map,
+    position: IVec2,
+    direction: Direction,
+    visited: HashMap<IVec2, HashSet<Direction>>,
+    looped: bool,
 }
 
-fn get_visited(map: &Map) -> Option<HashMap<IVec2, HashSet<IVec2>>> {
-    let mut position = map.guard.clone();
-    let mut direction = IVec2::NEG_Y;
-    let mut visited: HashMap<IVec2, HashSet<IVec2>> = HashMap::new();
+impl<'a> Patrol<'a> {
+    pub fn new(map: &'a Map) -> Self {
+        Self {
+            map,
+            position: map.guard,
+            direction: Direction::N,
+            visited: HashMap::new(),
+            looped: false,
+        }
+    }
+}
 
-    while in_map(map, position) {
-        if visited
-            .get(&position)
-            .map(|dirs| dirs.contains(&direction))
+impl Simulation for Patrol<'_> {
+    fn step(&mut self) -> bool {
+        if self.looped || !self.map.obstructions.in_bounds(self.position) {
+            return false;
+        }
+
+        if self
+            .visited
+            .get(&self.position)
+            .map(|dirs| dirs.contains(&self.direction))
             .unwrap_or(false)
         {
             // Hit a loop
-            return None;
+            self.looped = true;
+            return false;
         }
 
-        visited
-            .entry(position)
-            .or_insert_with(HashSet::new)
-            .insert(direction);
+        self.visited
+            .entry(self.position)
+            .or_default()
+            .insert(self.direction);
 
-        let in_front = position + direction;
-        if map.obstructions.contains(&in_front) {
-            direction = direction.perp();
-            continue;
+        let in_front = self.position + IVec2::from(self.direction);
+        if self.map.obstructions.get(in_front) == Some(&true) {
+            self.direction = self.direction.turn_right();
+        } else {
+            self.position = in_front;
         }
 
-        position += direction;
+        true
     }
+}
 
-    Some(visited)
+/// The path so far, with the guard pointing the way it's facing.
+impl Render for Patrol<'_> {
+    fn render(&self) -> Frame {
+        Frame::new(self.map.obstructions.map_with_position(|pos, &obstructed| {
+            if pos == self.position {
+                let arrow = self.direction.arrow().unwrap_or('?');
+                Cell::new(arrow).with_colour(Colour::Red).highlighted()
+            } else if obstructed {
+                Cell::new('#').with_colour(Colour::Grey)
+            } else if self.visited.contains_key(&pos) {
+                Cell::new('X').with_colour(Colour::Cyan)
+            } else {
+                Cell::new('.')
+            }
+        }))
+    }
+}
+
+fn get_visited(map: &Map) -> Option<HashMap<IVec2, HashSet<Direction>>> {
+    let mut patrol = Patrol::new(map);
+    while patrol.step() {}
+
+    if patrol.looped {
+        None
+    } else {
+        Some(patrol.visited)
+    }
 }
 
 #[aoc(day6, part1)]
-pub fn solve_part1(map: &Map) -> usize {
-    get_visited(map).unwrap().len()
+pub fn solve_part1(map: &Map) -> Option<usize> {
+    get_visited(map).map(|visited| visited.len())
+}
+
+/// Whether the guard walks in a loop from `position`, facing `direction`,
+/// once `extra` is obstructed as well.
+fn loops_with(map: &Map, extra: IVec2, mut position: IVec2, mut direction: Direction) -> bool {
+    let mut seen = HashSet::new();
+    while map.obstructions.in_bounds(position) {
+        if !seen.insert((position, direction)) {
+            return true;
+        }
+        let in_front = position + IVec2::from(direction);
+        if in_front == extra || map.obstructions.get(in_front) == Some(&true) {
+            direction = direction.turn_right();
+        } else {
+            position = in_front;
+        }
+    }
+    false
 }
 
 #[aoc(day6, part2)]
-pub fn solve_part2(map: &Map) -> usize {
-    let mut visited = get_visited(map).unwrap();
+pub fn solve_part2(map: &Map) -> Option<usize> {
+    get_visited(map)?;
+
+    // Walk the guard's path, and the first time it's about to step somewhere
+    // new, see whether blocking that spot sends it round in a loop. Nothing
+    // before that point on the path changes, so the check can start there.
+    let mut patrol = Patrol::new(map);
+    let mut tried = HashSet::from([map.guard]);
+    let mut loops = 0;
+    loop {
+        let in_front = patrol.position + IVec2::from(patrol.direction);
+        if map.obstructions.get(in_front) == Some(&false)
+            && tried.insert(in_front)
+            && loops_with(map, in_front, patrol.position, patrol.direction)
+        {
+            debug!(obstruction = ?in_front, "loop detected");
+            loops += 1;
+        }
+        if !patrol.step() {
+            return Some(loops);
+        }
+    }
+}
+
+/// Adds an obstruction to a copy of the map at each spot on the guard's path,
+/// to check [`solve_part2`] against.
+#[aoc(day6, part2, reference)]
+pub fn solve_part2_reference(map: &Map) -> Option<usize> {
+    let mut visited = get_visited(map)?;
     visited.remove(&map.guard);
 
-    visited
+    let loops = visited
         .iter()
         .filter(|(&v, _)| {
             let mut new_obstructions = map.obstructions.clone();
-            new_obstructions.insert(v);
+            new_obstructions.set(v, true);
             let new_map = Map {
-                size: map.size,
                 obstructions: new_obstructions,
                 guard: map.guard,
             };
 
             get_visited(&new_map).is_none()
         })
-        .count()
+        .count();
+    Some(loops)
+}
+
+/// A random map the guard walks out of, as in the puzzle.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(130, scale);
+    loop {
+        let guard = (rng.random_range(0..side), rng.random_range(0..side));
+        let input = char_grid(side, side, |x, y| {
+            if (x, y) == guard {
+                '^'
+            } else if rng.random_bool(0.048) {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        let map = input_generator(&input).expect("generated map should parse");
+        if get_visited(&map).is_some() {
+            return input;
+        }
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        solve_part1(input).map(Answer::from)
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        solve_part2(input).map(Answer::from)
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn simulate(input: &Self::Input, _part: u32) -> Option<Box<dyn Simulation + '_>> {
+        Some(Box::new(Patrol::new(input)))
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+
+    fn reference(part: u32) -> Option<Part<Self::Input>> {
+        match part {
+            2 => Some(|input| solve_part2_reference(input).map(Answer::from)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::fixture::Fixture;
+
     #[test]
     fn test_day6() {
-        let input = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
-        let map = super::input_generator(input);
-        dbg!(super::solve_part2(&map));
+        let input = Fixture::load(6, "example").unwrap().input;
+        let map = super::input_generator(&input).unwrap();
+        assert_eq!(super::solve_part2_reference(&map), Some(6));
     }
 }
@@ -2,21 +2,19 @@ use std::iter::once;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::{repeat_n, Itertools};
-use nom::{
-    bytes::complete::tag,
-    character::complete::{i64, newline},
-    multi::separated_list1,
-    sequence::separated_pair,
+use nom::{character::complete::i64, sequence::separated_pair};
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    answer::Answer,
+    generate::{count, number},
+    parse::{finish, lines, spaced, tag, ParseError},
+    solution::{Part, Solution},
 };
 
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Vec<(i64, Vec<i64>)> {
-    separated_list1(
-        newline::<&str, nom::error::Error<&str>>,
-        separated_pair(i64, tag(": "), separated_list1(tag(" "), i64)),
-    )(input)
-    .unwrap()
-    .1
+pub fn input_generator(input: &str) -> Result<Vec<(i64, Vec<i64>)>, ParseError> {
+    finish(input, lines(separated_pair(i64, tag(": "), spaced(i64))))
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -28,7 +26,7 @@ enum Operator {
 
 fn get_operators(
     solution: i64,
-    operands: &Vec<i64>,
+    operands: &[i64],
     valid_operators: Vec<Operator>,
 ) -> Option<Vec<Operator>> {
     let num_operators = operands.len() - 1;
@@ -37,7 +35,7 @@ fn get_operators(
     possible_arrangements.into_iter().find(|operators| {
         let result = operands
             .iter()
-            .zip(once(&Operator::Add).chain(operators.into_iter()))
+            .zip(once(&Operator::Add).chain(operators.iter()))
             .fold(0, |acc, (operand, operator)| match operator {
                 Operator::Add => acc + operand,
                 Operator::Multiply => acc * operand,
@@ -48,8 +46,58 @@ fn get_operators(
     })
 }
 
+/// Whether `operands` can be combined into `solution`. Works backwards from
+/// the last operand, undoing each operator, so most branches end early.
+fn can_make(solution: i64, operands: &[i64], concat: bool) -> bool {
+    let Some((&last, rest)) = operands.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return solution == last;
+    }
+
+    let add = solution >= last && can_make(solution - last, rest, concat);
+    let multiply = || {
+        if last == 0 {
+            solution == 0
+        } else {
+            solution % last == 0 && can_make(solution / last, rest, concat)
+        }
+    };
+    let unconcat = || {
+        // A shift too big for an `i64` can't leave anything to undo.
+        let Some(shift) = 10i64.checked_pow(last.to_string().len() as u32) else {
+            return false;
+        };
+        solution >= last
+            && (solution - last) % shift == 0
+            && can_make(solution / shift, rest, concat)
+    };
+
+    add || multiply() || (concat && unconcat())
+}
+
 #[aoc(day7, part1)]
-pub fn solve_part1(formulas: &Vec<(i64, Vec<i64>)>) -> i64 {
+pub fn solve_part1(formulas: &[(i64, Vec<i64>)]) -> i64 {
+    formulas
+        .iter()
+        .filter(|(solution, operands)| can_make(*solution, operands, false))
+        .map(|(solution, _)| solution)
+        .sum()
+}
+
+#[aoc(day7, part2)]
+pub fn solve_part2(formulas: &[(i64, Vec<i64>)]) -> i64 {
+    formulas
+        .iter()
+        .filter(|(solution, operands)| can_make(*solution, operands, true))
+        .map(|(solution, _)| solution)
+        .sum()
+}
+
+/// Tries every arrangement of operators, to check [`solve_part1`] against.
+#[aoc(day7, part1, reference)]
+pub fn solve_part1_reference(formulas: &[(i64, Vec<i64>)]) -> i64 {
     let valid_formulas = formulas.iter().filter(|(solution, operands)| {
         get_operators(*solution, operands, vec![Operator::Add, Operator::Multiply]).is_some()
     });
@@ -57,8 +105,9 @@ pub fn solve_part1(formulas: &Vec<(i64, Vec<i64>)>) -> i64 {
     valid_formulas.map(|(solution, _)| solution).sum()
 }
 
-#[aoc(day7, part2)]
-pub fn solve_part2(formulas: &Vec<(i64, Vec<i64>)>) -> i64 {
+/// Tries every arrangement of operators, to check [`solve_part2`] against.
+#[aoc(day7, part2, reference)]
+pub fn solve_part2_reference(formulas: &[(i64, Vec<i64>)]) -> i64 {
     let valid_formulas = formulas.iter().filter(|(solution, operands)| {
         get_operators(
             *solution,
@@ -70,3 +119,89 @@ pub fn solve_part2(formulas: &Vec<(i64, Vec<i64>)>) -> i64 {
 
     valid_formulas.map(|(solution, _)| solution).sum()
 }
+
+/// Random equations, about half of which can be made true.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    (0..count(850, scale))
+        .map(|_| {
+            // With at most 18 digits between the operands, no way of combining
+            // them can overflow.
+            let len = rng.random_range(2..=12);
+            let mut operands: Vec<i64> = Vec::new();
+            let mut digits = 0;
+            while operands.len() < len {
+                let operand = if rng.random_bool(0.5) {
+                    rng.random_range(1..10)
+                } else {
+                    number(rng, 2, 3) as i64
+                };
+                digits += operand.to_string().len();
+                if digits > 18 {
+                    break;
+                }
+                operands.push(operand);
+            }
+
+            let mut solution = operands[0];
+            for &operand in &operands[1..] {
+                solution = match rng.random_range(0..3) {
+                    0 => solution + operand,
+                    1 => solution * operand,
+                    _ => format!("{}{}", solution, operand).parse().unwrap(),
+                };
+            }
+            if rng.random_bool(0.5) {
+                solution += rng.random_range(1..=100);
+            }
+
+            format!("{}: {}\n", solution, operands.iter().join(" "))
+        })
+        .collect()
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<(i64, Vec<i64>)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+
+    fn reference(part: u32) -> Option<Part<Self::Input>> {
+        match part {
+            1 => Some(|input| Some(solve_part1_reference(input).into())),
+            2 => Some(|input| Some(solve_part2_reference(input).into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_make() {
+        assert!(can_make(7290, &[6, 8, 6, 15], true));
+        assert!(!can_make(7290, &[6, 8, 6, 15], false));
+
+        let big = 1_000_000_000_000_000_000;
+        assert!(can_make(big, &[1, big], true));
+        assert!(!can_make(big, &[2, big], true));
+    }
+}
@@ -6,36 +6,29 @@ use std::{
 use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec2;
 use itertools::Itertools;
-use nom::{
-    character::{
-        complete::{line_ending, satisfy},
-        is_alphanumeric,
-    },
-    multi::many1,
-    sequence::terminated,
-    IResult,
+use nom::character::{complete::satisfy, is_alphanumeric};
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt};
+use tracing::trace;
+
+use crate::{
+    answer::Answer,
+    generate::{char_grid, count, side},
+    grid::Grid,
+    parse::{finish, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::Solution,
 };
 
 #[derive(Debug, Clone)]
 pub struct Map {
-    size: IVec2,
+    grid: Grid<char>,
     antennas: HashMap<char, Vec<IVec2>>,
     pub resonant_harmonics: bool,
 }
 
 impl Map {
-    fn get_antenna(&self, position: IVec2) -> Option<char> {
-        for (c, antennas) in &self.antennas {
-            if antennas.contains(&position) {
-                return Some(*c);
-            }
-        }
-
-        None
-    }
-
     fn in_bounds(&self, position: &IVec2) -> bool {
-        position.x >= 0 && position.x < self.size.x && position.y >= 0 && position.y < self.size.y
+        self.grid.in_bounds(*position)
     }
 
     fn antenna_pair_antinodes(&self, a: &IVec2, b: &IVec2) -> HashSet<IVec2> {
@@ -43,14 +36,14 @@ impl Map {
 
         if self.resonant_harmonics {
             let d = b - a;
-            let mut curr = a.clone();
+            let mut curr = *a;
             while self.in_bounds(&curr) {
                 antinodes.insert(curr);
                 curr -= d;
             }
 
             let d = a - b;
-            curr = b.clone();
+            curr = *b;
             while self.in_bounds(&curr) {
                 antinodes.insert(curr);
                 curr -= d;
@@ -82,76 +75,58 @@ impl Map {
     }
 }
 
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Antennas are coloured by frequency and antinodes are highlighted.
+impl Render for Map {
+    fn render(&self) -> Frame {
         let antinodes = self.get_antinodes();
-        for y in 0..self.size.y {
-            for x in 0..self.size.x {
-                let pos = IVec2::new(x, y);
-                let antenna = self.get_antenna(pos);
-                if let Some(c) = antenna {
-                    write!(f, "{}", c)?;
-                } else if antinodes.contains(&pos) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
+        Frame::new(self.grid.map_with_position(|pos, &c| {
+            let cell = match c {
+                '.' if antinodes.contains(&pos) => Cell::new('#').with_colour(Colour::Red),
+                '.' => Cell::new('.'),
+                c => Cell::new(c).with_colour(Colour::PALETTE[c as usize % Colour::PALETTE.len()]),
+            };
+            if antinodes.contains(&pos) {
+                cell.highlighted()
+            } else {
+                cell
             }
-            if y != self.size.y - 1 {
-                writeln!(f)?;
-            }
-        }
-
-        Ok(())
+        }))
     }
 }
 
-fn end_of_line(input: &str) -> IResult<&str, &str> {
-    if input.is_empty() {
-        Ok((input, input))
-    } else {
-        line_ending(input)
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
     }
 }
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Map {
-    let raw_map = many1(terminated(
-        many1(satisfy(|c| c == '.' || is_alphanumeric(c as u8))),
-        end_of_line,
-    ))(input)
-    .unwrap()
-    .1;
-
-    let height = raw_map.len();
-    let width = raw_map[0].len();
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    let grid = finish(
+        input,
+        Grid::parse(satisfy(|c| c == '.' || is_alphanumeric(c as u8))),
+    )?;
 
     let mut antennas: HashMap<char, Vec<IVec2>> = HashMap::new();
 
-    for y in 0..height {
-        for x in 0..width {
-            let c = raw_map[y][x];
-            if c != '.' {
-                antennas
-                    .entry(c)
-                    .or_default()
-                    .push(IVec2::new(x as i32, y as i32));
-            }
+    for (pos, &c) in grid.iter() {
+        if c != '.' {
+            antennas.entry(c).or_default().push(pos);
         }
     }
 
-    Map {
-        size: IVec2::new(width as i32, height as i32),
+    Ok(Map {
+        grid,
         antennas,
         resonant_harmonics: false,
-    }
+    })
 }
 
 #[aoc(day8, part1)]
 pub fn solve_part1(map: &Map) -> usize {
     let antinodes = map.get_antinodes();
 
-    println!("{}", map);
+    trace!("antinodes:\n{}", map);
 
     antinodes.len()
 }
@@ -163,26 +138,58 @@ pub fn solve_part2(map: &Map) -> usize {
     solve_part1(&map)
 }
 
+/// A random map with about four antennas per frequency, like the real one.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let side = side(50, scale);
+    let antennas = count(235, scale);
+    let frequencies: Vec<char> = ('0'..='9').chain('a'..='z').chain('A'..='Z').collect();
+    let frequencies = &frequencies[..(antennas / 4).clamp(1, frequencies.len())];
+
+    let mut cells = vec!['.'; side * side];
+    for _ in 0..antennas {
+        cells[rng.random_range(0..side * side)] = *frequencies.choose(rng).unwrap();
+    }
+    char_grid(side, side, |x, y| cells[y * side + x])
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixture::Fixture;
 
     #[test]
     fn test_day8_part1() {
-        let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
+        let input = Fixture::load(8, "example").unwrap().input;
 
-        let map = input_generator(&input);
+        let map = input_generator(&input).unwrap();
 
         let output = format!("{}", map);
 
@@ -202,25 +209,14 @@ mod tests {
 ..........#."
         );
 
-        assert_eq!(solve_part1(&map), 9);
+        assert_eq!(solve_part1(&map), 14);
     }
 
     #[test]
     fn test_day8_part2() {
-        let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
+        let input = Fixture::load(8, "example").unwrap().input;
 
-        let map = input_generator(&input);
+        let map = input_generator(&input).unwrap();
 
         let output = solve_part2(&map);
 
@@ -1,58 +1,134 @@
 use std::fmt::Display;
 
 use aoc_runner_derive::{aoc, aoc_generator};
+use glam::IVec2;
 use itertools::{repeat_n, Itertools};
+use nom::{character::complete::satisfy, multi::many1, Parser};
+use rand::{rngs::StdRng, RngExt};
+
+use crate::{
+    animate::Simulation,
+    answer::Answer,
+    generate::count,
+    grid::Grid,
+    parse::{finish, ParseError},
+    render::{Cell, Colour, Frame, Render},
+    solution::Solution,
+};
 
 type Block = Option<usize>;
 
+#[derive(Debug, Clone)]
 pub struct Disk {
     blocks: Vec<Block>,
 }
 
 impl Disk {
     pub fn compact(&self) -> Disk {
-        let mut blocks = self.blocks.clone();
+        let mut compaction = Compaction::new(self);
+        while compaction.step() {}
+        compaction.disk
+    }
 
-        let mut i = 0;
+    pub fn compact_whole_files(&self) -> Disk {
+        let mut compaction = FileCompaction::new(self);
+        while compaction.step() {}
+        compaction.disk
+    }
 
-        for (j, _) in blocks
-            .clone()
+    pub fn checksum(&self) -> usize {
+        self.blocks
             .iter()
             .enumerate()
-            .rev()
-            .filter(|(_, b)| b.is_some())
-        {
-            while blocks[i].is_some() && i < blocks.len() {
-                i += 1;
-            }
+            .map(|(i, b)| i * b.unwrap_or(0))
+            .sum()
+    }
+}
 
-            if i >= j {
-                break;
-            }
+/// Moves blocks one at a time from the end of the disk to the leftmost free
+/// block.
+pub struct Compaction {
+    disk: Disk,
+    /// The leftmost block that might be free.
+    free: usize,
+    /// One past the rightmost block that might be used.
+    end: usize,
+}
+
+impl Compaction {
+    pub fn new(disk: &Disk) -> Self {
+        Self {
+            disk: disk.clone(),
+            free: 0,
+            end: disk.blocks.len(),
+        }
+    }
+}
+
+impl Simulation for Compaction {
+    fn step(&mut self) -> bool {
+        let blocks = &mut self.disk.blocks;
+        while self.free < blocks.len() && blocks[self.free].is_some() {
+            self.free += 1;
+        }
+        while self.end > 0 && blocks[self.end - 1].is_none() {
+            self.end -= 1;
+        }
 
-            blocks.swap(i, j);
+        if self.end == 0 || self.free >= self.end - 1 {
+            return false;
         }
 
-        Disk { blocks }
+        blocks.swap(self.free, self.end - 1);
+        true
     }
+}
 
-    pub fn compact_whole_files(&self) -> Disk {
-        let mut blocks = self.blocks.clone();
+impl Render for Compaction {
+    fn render(&self) -> Frame {
+        self.disk.render()
+    }
+}
 
-        for (_, file_blocks) in &blocks
-            .clone()
+/// Moves whole files, highest ID first, to the leftmost free span that fits
+/// them, one file per step.
+pub struct FileCompaction {
+    disk: Disk,
+    /// The start and length of each file still to move, in the order they're
+    /// moved.
+    files: Vec<(usize, usize)>,
+}
+
+impl FileCompaction {
+    pub fn new(disk: &Disk) -> Self {
+        let files = disk
+            .blocks
             .iter()
             .enumerate()
             .rev()
             .filter(|(_, b)| b.is_some())
             .chunk_by(|(_, b)| **b)
-        {
-            let mut i = 0;
-            let mut file_blocks = file_blocks.collect_vec();
-            file_blocks.reverse();
-            let file_size = file_blocks.len();
-            let file_start_index = file_blocks[0].0;
+            .into_iter()
+            .map(|(_, file_blocks)| {
+                let file_blocks = file_blocks.collect_vec();
+                (file_blocks[file_blocks.len() - 1].0, file_blocks.len())
+            })
+            .collect_vec();
 
+        Self {
+            disk: disk.clone(),
+            files: files.into_iter().rev().collect(),
+        }
+    }
+}
+
+impl Simulation for FileCompaction {
+    /// Skips over files that don't fit anywhere, so every step moves one.
+    fn step(&mut self) -> bool {
+        let blocks = &mut self.disk.blocks;
+
+        while let Some((file_start_index, file_size)) = self.files.pop() {
+            let mut i = 0;
             while i < file_start_index && blocks[i..i + file_size].iter().any(|b| b.is_some()) {
                 i += 1;
             }
@@ -61,41 +137,58 @@ impl Disk {
                 continue;
             }
 
-            for (k, (j, _)) in file_blocks.iter().enumerate() {
-                blocks.swap(i + k, *j);
+            for k in 0..file_size {
+                blocks.swap(i + k, file_start_index + k);
             }
+            return true;
         }
 
-        Disk { blocks }
+        false
+    }
+}
+
+impl Render for FileCompaction {
+    fn render(&self) -> Frame {
+        self.disk.render()
     }
+}
 
-    pub fn checksum(&self) -> usize {
-        self.blocks
+/// A single row of blocks, each showing the last digit of its file ID and
+/// coloured by file so that neighbouring files can be told apart.
+impl Render for Disk {
+    fn render(&self) -> Frame {
+        let row = self
+            .blocks
             .iter()
-            .enumerate()
-            .map(|(i, b)| i * b.unwrap_or(0))
-            .sum()
+            .map(|block| match block {
+                Some(id) => Cell::new(char::from(b'0' + (id % 10) as u8))
+                    .with_colour(Colour::PALETTE[id % Colour::PALETTE.len()]),
+                None => Cell::new('.').with_colour(Colour::Grey),
+            })
+            .collect();
+        // An empty disk is a row with no blocks in it.
+        Frame::new(
+            Grid::from_rows(vec![row])
+                .unwrap_or_else(|| Grid::new(IVec2::new(0, 1), Cell::new('.'))),
+        )
     }
 }
 
 impl Display for Disk {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.blocks
-                .iter()
-                .map(|b| b.map_or(String::from("."), |b| b.to_string()))
-                .join("")
-        )
+        write!(f, "{}", self.render())
     }
 }
 
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Disk {
-    let blocks = input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize)
+pub fn input_generator(input: &str) -> Result<Disk, ParseError> {
+    let digits = finish(
+        input,
+        many1(satisfy(|c| c.is_ascii_digit()).map(|c| c.to_digit(10).unwrap() as usize)),
+    )?;
+
+    let blocks = digits
+        .into_iter()
         .enumerate()
         .flat_map(|(i, n)| {
             let block = if i % 2 == 0 { Some(i / 2) } else { None };
@@ -103,7 +196,7 @@ pub fn input_generator(input: &str) -> Disk {
         })
         .collect();
 
-    Disk { blocks }
+    Ok(Disk { blocks })
 }
 
 #[aoc(day9, part1)]
@@ -118,45 +211,142 @@ pub fn solve_part2(disk: &Disk) -> usize {
     compacted.checksum()
 }
 
+/// A random disk map, with no empty files.
+fn random_input(rng: &mut StdRng, scale: f64) -> String {
+    let len = count(10000, scale) * 2 - 1;
+    let digits: String = (0..len)
+        .map(|i| {
+            let n = if i % 2 == 0 {
+                rng.random_range(1..=9)
+            } else {
+                rng.random_range(0..=9)
+            };
+            char::from_digit(n, 10).unwrap()
+        })
+        .collect();
+    digits + "\n"
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    type Input = Disk;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(solve_part2(input).into())
+    }
+
+    fn render(input: &Self::Input) -> Option<Frame> {
+        Some(input.render())
+    }
+
+    fn simulate(input: &Self::Input, part: u32) -> Option<Box<dyn Simulation + '_>> {
+        if part == 1 {
+            Some(Box::new(Compaction::new(input)))
+        } else {
+            Some(Box::new(FileCompaction::new(input)))
+        }
+    }
+
+    fn generate(rng: &mut StdRng, scale: f64) -> Option<String> {
+        Some(random_input(rng, scale))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::fixture::Fixture;
+
+    /// File blocks by ID, however they are laid out.
+    fn file_blocks(disk: &Disk) -> Vec<usize> {
+        disk.blocks.iter().flatten().copied().sorted().collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_day9_display_matches_disk_map(digits in prop::collection::vec(0..10usize, 1..40)) {
+            let input: String = digits.iter().map(|d| d.to_string()).collect();
+            let disk = input_generator(&input).unwrap();
+
+            let expected: String = digits
+                .iter()
+                .enumerate()
+                .map(|(i, &n)| {
+                    let c = if i % 2 == 0 {
+                        char::from_digit((i / 2 % 10) as u32, 10).unwrap()
+                    } else {
+                        '.'
+                    };
+                    c.to_string().repeat(n)
+                })
+                .collect();
+            prop_assert_eq!(disk.to_string(), expected);
+        }
+
+        #[test]
+        fn test_day9_compact_keeps_file_blocks(digits in prop::collection::vec(0..10usize, 1..40)) {
+            let input: String = digits.iter().map(|d| d.to_string()).collect();
+            let disk = input_generator(&input).unwrap();
+
+            prop_assert_eq!(file_blocks(&disk.compact()), file_blocks(&disk));
+            prop_assert_eq!(file_blocks(&disk.compact_whole_files()), file_blocks(&disk));
+        }
+    }
 
     #[test]
     fn test_day9_input_generator() {
-        let input = "2333133121414131402";
+        let input = Fixture::load(9, "example").unwrap().input;
 
-        let disk = input_generator(&input);
+        let disk = input_generator(&input).unwrap();
 
         assert_eq!(
             format!("{}", disk),
             "00...111...2...333.44.5555.6666.777.888899"
         );
+
+        let disk = input_generator(input.trim_end()).unwrap();
+        assert_eq!(disk.checksum(), input_generator(&input).unwrap().checksum());
+
+        assert_eq!(input_generator("0").unwrap().to_string(), "");
+
+        let error = input_generator("23331x3").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 6));
     }
 
     #[test]
     fn test_day9_compact() {
-        let input = "2333133121414131402";
+        let input = Fixture::load(9, "example").unwrap().input;
 
-        let disk = input_generator(&input);
+        let disk = input_generator(&input).unwrap();
         let compacted = disk.compact();
         assert_eq!(
             format!("{}", compacted),
             "0099811188827773336446555566.............."
         );
-        assert_eq!(compacted.checksum(), 1928);
     }
 
     #[test]
     fn test_day9_compact_whole_files() {
-        let input = "2333133121414131402";
+        let input = Fixture::load(9, "example").unwrap().input;
 
-        let disk = input_generator(&input);
+        let disk = input_generator(&input).unwrap();
         let compacted = disk.compact_whole_files();
         assert_eq!(
             format!("{}", compacted),
             "00992111777.44.333....5555.6666.....8888.."
         );
-        assert_eq!(compacted.checksum(), 2858);
     }
 }
@@ -0,0 +1,260 @@
+use std::{
+    fmt::Display,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    answer::Answer,
+    generate::generate,
+    solution::{solutions, DynSolution},
+};
+
+/// What an implementation made of an input: its answer, or the message it
+/// panicked with.
+pub type Outcome = Result<Option<Answer>, String>;
+
+/// An input that a day's reference and optimized implementations disagree on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement {
+    pub day: u32,
+    pub part: u32,
+    /// The seed of the generated input that `input` came from.
+    pub seed: u64,
+    pub input: String,
+    /// Whether `input` is the generated input shrunk, rather than as it was
+    /// generated because the shrunk one stopped disagreeing.
+    pub shrunk: bool,
+    pub reference: Outcome,
+    pub optimized: Outcome,
+}
+
+impl Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |outcome: &Outcome| match outcome {
+            Ok(Some(answer)) => answer.to_string(),
+            Ok(None) => "no answer".to_string(),
+            Err(message) => format!("panicked: {}", message),
+        };
+        writeln!(
+            f,
+            "Day {} part {} disagrees on seed {}, {}:",
+            self.day,
+            self.part,
+            self.seed,
+            if self.shrunk {
+                "shrunk to"
+            } else {
+                "as generated"
+            }
+        )?;
+        write!(f, "{}", self.input)?;
+        writeln!(f, "reference: {}", describe(&self.reference))?;
+        write!(f, "optimized: {}", describe(&self.optimized))
+    }
+}
+
+/// Every day and part with a reference implementation.
+pub fn checkable() -> impl Iterator<Item = (&'static dyn DynSolution, u32)> {
+    solutions()
+        .iter()
+        .flat_map(|&solution| [1, 2].map(|part| (solution, part)))
+        .filter(|(solution, part)| solution.has_reference(*part))
+}
+
+fn outcome(solve: impl FnOnce() -> Option<Answer>) -> Outcome {
+    panic::catch_unwind(AssertUnwindSafe(solve)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+/// Runs both implementations of `part` on `input`, returning what they made
+/// of it if they disagree. Inputs that don't parse, or that both panic on,
+/// aren't disagreements.
+fn compare(solution: &dyn DynSolution, part: u32, input: &str) -> Option<(Outcome, Outcome)> {
+    let parsed = solution.parse_any(input).ok()?;
+    let reference = outcome(|| solution.solve_reference_any(part, parsed.as_ref()));
+    let optimized = outcome(|| solution.solve_any(part, parsed.as_ref()));
+    match (&reference, &optimized) {
+        (Err(_), Err(_)) => None,
+        _ => (reference != optimized).then_some((reference, optimized)),
+    }
+}
+
+/// Checks `part` of `solution` against its reference on a generated input
+/// for each of `seeds`, about `scale` times the size of the real one. Returns
+/// the first input they disagree on, shrunk as far as it will go while they
+/// still do.
+///
+/// # Panics
+///
+/// If the day has no reference for `part`, or can't generate inputs.
+pub fn check(
+    solution: &dyn DynSolution,
+    part: u32,
+    seeds: Range<u64>,
+    scale: f64,
+) -> Option<Disagreement> {
+    assert!(
+        solution.has_reference(part),
+        "day {} has no reference for part {}",
+        solution.day(),
+        part
+    );
+
+    for seed in seeds {
+        let input = generate(solution.day(), seed, scale)
+            .unwrap_or_else(|| panic!("day {} can't generate inputs", solution.day()));
+        let Some(outcomes) = compare(solution, part, &input) else {
+            continue;
+        };
+
+        // Shrinking rewrites the input line by line, so if even the shrunk
+        // input doesn't disagree any more, report the one that did.
+        let shrunk = shrink(&input, |input| compare(solution, part, input).is_some());
+        let (input, shrunk, (reference, optimized)) = match compare(solution, part, &shrunk) {
+            Some(shrunk_outcomes) => (shrunk, true, shrunk_outcomes),
+            None => (input, false, outcomes),
+        };
+        return Some(Disagreement {
+            day: solution.day(),
+            part,
+            seed,
+            input,
+            shrunk,
+            reference,
+            optimized,
+        });
+    }
+
+    None
+}
+
+fn join_lines(lines: &[Vec<char>]) -> String {
+    lines
+        .iter()
+        .map(|line| line.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Shrinks `input` for as long as `fails` still holds: first by dropping
+/// runs of lines, then single words, then by clearing grid cells to `.`.
+fn shrink(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let mut lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+    let mut try_lines = |candidate: Vec<Vec<char>>, lines: &mut Vec<Vec<char>>| {
+        let ok = !candidate.is_empty() && fails(&join_lines(&candidate));
+        if ok {
+            *lines = candidate;
+        }
+        ok
+    };
+
+    loop {
+        let mut shrunk = false;
+
+        let mut run = lines.len() / 2;
+        while run > 0 {
+            let mut start = 0;
+            while start < lines.len() {
+                let end = (start + run).min(lines.len());
+                let candidate = [&lines[..start], &lines[end..]].concat();
+                if try_lines(candidate, &mut lines) {
+                    shrunk = true;
+                } else {
+                    start += run;
+                }
+            }
+            run /= 2;
+        }
+
+        for i in 0..lines.len() {
+            let mut word = 0;
+            loop {
+                let line: String = lines[i].iter().collect();
+                let words = line.split(' ').collect_vec();
+                if word >= words.len() || words.len() == 1 {
+                    break;
+                }
+                let mut candidate = lines.clone();
+                candidate[i] = words
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != word)
+                    .map(|(_, w)| w)
+                    .join(" ")
+                    .chars()
+                    .collect();
+                if try_lines(candidate, &mut lines) {
+                    shrunk = true;
+                } else {
+                    word += 1;
+                }
+            }
+        }
+
+        if lines.iter().flatten().any(|&c| c == '.') {
+            for i in 0..lines.len() {
+                for j in 0..lines[i].len() {
+                    if lines[i][j] != '.' {
+                        let mut candidate = lines.clone();
+                        candidate[i][j] = '.';
+                        shrunk |= try_lines(candidate, &mut lines);
+                    }
+                }
+            }
+        }
+
+        if !shrunk {
+            return join_lines(&lines);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references_agree() {
+        for (solution, part) in checkable() {
+            assert_eq!(check(solution, part, 0..3, 0.005), None);
+        }
+    }
+
+    #[test]
+    fn test_disagreement_display() {
+        let mut disagreement = Disagreement {
+            day: 7,
+            part: 2,
+            seed: 3,
+            input: "1: 1\n".to_string(),
+            shrunk: true,
+            reference: Ok(Some(Answer::Unsigned(1))),
+            optimized: Err("overflow".to_string()),
+        };
+        assert_eq!(
+            disagreement.to_string(),
+            "Day 7 part 2 disagrees on seed 3, shrunk to:\n1: 1\nreference: 1\noptimized: panicked: overflow"
+        );
+
+        disagreement.shrunk = false;
+        assert!(disagreement
+            .to_string()
+            .starts_with("Day 7 part 2 disagrees on seed 3, as generated:\n"));
+    }
+
+    #[test]
+    fn test_shrink() {
+        let fails = |input: &str| input.contains('5') && input.contains('#');
+        assert_eq!(shrink("1 2 3\n4 5 6\n7 8 9\n..#\n", fails), "5\n..#\n");
+
+        let fails = |input: &str| input.matches('#').count() >= 2;
+        assert_eq!(shrink("#.#\n.#.\n", fails), "#.#\n");
+    }
+}
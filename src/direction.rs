@@ -0,0 +1,162 @@
+use std::fmt::Display;
+
+use glam::IVec2;
+use strum_macros::EnumIter;
+
+/// A compass direction on a grid where north is towards negative `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    pub const ORDINAL: [Direction; 4] =
+        [Direction::NE, Direction::SE, Direction::SW, Direction::NW];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self.index() + eighths) % 8]
+    }
+
+    /// Rotates 90 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Rotates 90 degrees counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        Self::CARDINAL.contains(&self)
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::E | Direction::W)
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::N | Direction::S)
+    }
+
+    /// The `^>v<` arrow for a cardinal direction.
+    pub fn arrow(self) -> Option<char> {
+        match self {
+            Direction::N => Some('^'),
+            Direction::E => Some('>'),
+            Direction::S => Some('v'),
+            Direction::W => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for IVec2 {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Direction::N => IVec2::new(0, -1),
+            Direction::NE => IVec2::new(1, -1),
+            Direction::E => IVec2::new(1, 0),
+            Direction::SE => IVec2::new(1, 1),
+            Direction::S => IVec2::new(0, 1),
+            Direction::SW => IVec2::new(-1, 1),
+            Direction::W => IVec2::new(-1, 0),
+            Direction::NW => IVec2::new(-1, -1),
+        }
+    }
+}
+
+impl TryFrom<IVec2> for Direction {
+    type Error = IVec2;
+
+    fn try_from(offset: IVec2) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|&dir| IVec2::from(dir) == offset)
+            .ok_or(offset)
+    }
+}
+
+/// Parses either a `^>v<` arrow or an `NESW` compass letter.
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'N' => Ok(Direction::N),
+            '>' | 'E' => Ok(Direction::E),
+            'v' | 'S' => Ok(Direction::S),
+            '<' | 'W' => Ok(Direction::W),
+            _ => Err(c),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::N.turn_right(), Direction::E);
+        assert_eq!(Direction::N.turn_left(), Direction::W);
+        assert_eq!(Direction::NE.turn_right(), Direction::SE);
+        assert_eq!(Direction::SW.reverse(), Direction::NE);
+
+        for dir in Direction::iter() {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.reverse().reverse(), dir);
+            assert_eq!(IVec2::from(dir.reverse()), -IVec2::from(dir));
+            assert_eq!(Direction::try_from(IVec2::from(dir)), Ok(dir));
+        }
+
+        // glam's `perp` is a clockwise turn when y points down.
+        assert_eq!(IVec2::from(Direction::N).perp(), Direction::E.into());
+    }
+
+    #[test]
+    fn test_direction_parse() {
+        assert_eq!(Direction::try_from('^'), Ok(Direction::N));
+        assert_eq!(Direction::try_from('>'), Ok(Direction::E));
+        assert_eq!(Direction::try_from('S'), Ok(Direction::S));
+        assert_eq!(Direction::try_from('W'), Ok(Direction::W));
+        assert_eq!(Direction::try_from('x'), Err('x'));
+        assert_eq!(Direction::try_from(IVec2::new(2, 0)), Err(IVec2::new(2, 0)));
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use nom::{
+    bytes::complete::is_not,
+    character::complete::{one_of, space0},
+    combinator::map,
+    sequence::{preceded, separated_pair, tuple},
+};
+
+use crate::{
+    answer::Answer,
+    parse::{blank_line, finish, tag, IResult, ParseError},
+};
+
+/// The directory fixtures are kept in, one subdirectory per day, under
+/// [`crate::ROOT`].
+pub const DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// A puzzle example and the answers it should give, stored as
+/// `fixtures/dayN/<name>.txt`. The file starts with a `partN: answer` line
+/// for each part with a known answer, then a blank line, then the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub day: u32,
+    pub name: String,
+    pub input: String,
+    answers: [Option<Answer>; 2],
+}
+
+/// Why a fixture couldn't be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            LoadError::Parse(path, e) => write!(f, "failed to parse {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn parse_answer(input: &str) -> IResult<&str, (u32, Answer)> {
+    map(
+        separated_pair(
+            preceded(tag("part"), one_of("12")),
+            tuple((tag(":"), space0)),
+            is_not("\r\n"),
+        ),
+        |(part, answer): (char, &str)| {
+            let answer = answer.trim_end().replace("\\n", "\n");
+            let Ok(answer) = answer.parse();
+            (part.to_digit(10).unwrap(), answer)
+        },
+    )(input)
+}
+
+impl Fixture {
+    /// Parses the contents of a fixture file. Multi-line answers have their
+    /// lines joined with a literal `\n`, as in the answers registry.
+    pub fn parse(day: u32, name: impl Into<String>, contents: &str) -> Result<Self, ParseError> {
+        let (header, input) = contents
+            .match_indices(['\r', '\n'])
+            .find_map(|(i, _)| {
+                let (input, _) = blank_line::<nom::error::Error<_>>(&contents[i..]).ok()?;
+                Some((&contents[..i], input))
+            })
+            .ok_or_else(|| ParseError::new(contents, "", "a blank line before the input"))?;
+
+        let mut answers = [None, None];
+        for (i, line) in header.lines().enumerate() {
+            let (part, answer) =
+                finish(line, parse_answer).map_err(|e| ParseError { line: i + 1, ..e })?;
+            answers[part as usize - 1] = Some(answer);
+        }
+
+        Ok(Self {
+            day,
+            name: name.into(),
+            input: input.to_string(),
+            answers,
+        })
+    }
+
+    /// Where the fixture called `name` for `day` is kept.
+    pub fn path(day: u32, name: &str) -> PathBuf {
+        Path::new(DIR)
+            .join(format!("day{}", day))
+            .join(format!("{}.txt", name))
+    }
+
+    pub fn load(day: u32, name: &str) -> Result<Self, LoadError> {
+        let path = Self::path(day, name);
+        let contents = fs::read_to_string(&path).map_err(|e| LoadError::Io(path.clone(), e))?;
+        Self::parse(day, name, &contents).map_err(|e| LoadError::Parse(path, e))
+    }
+
+    /// The answer `part` should give, if it's known.
+    pub fn answer(&self, part: u32) -> Option<&Answer> {
+        self.answers.get(part.checked_sub(1)? as usize)?.as_ref()
+    }
+}
+
+/// The names of `day`'s fixtures, in order.
+pub fn names(day: u32) -> Result<Vec<String>, LoadError> {
+    let dir = Path::new(DIR).join(format!("day{}", day));
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(LoadError::Io(dir, e)),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| LoadError::Io(dir.clone(), e))?.path();
+        if path.extension().is_some_and(|e| e == "txt") {
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Every fixture for `day`, in order of name.
+pub fn load_day(day: u32) -> Result<Vec<Fixture>, LoadError> {
+    names(day)?
+        .iter()
+        .map(|name| Fixture::load(day, name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::solution;
+
+    /// Solves the fixture in `contents` and checks it gives its answers. The
+    /// tests calling this are generated by the build script, one per file.
+    fn check(day: u32, name: &str, contents: &str) {
+        let fixture = Fixture::parse(day, name, contents).unwrap();
+        assert!(
+            fixture.answer(1).is_some() || fixture.answer(2).is_some(),
+            "day {} fixture {} has no answers",
+            day,
+            name
+        );
+
+        let solution = solution(day).unwrap_or_else(|| panic!("no solution for day {}", day));
+        let parsed = solution.parse_any(&fixture.input).unwrap();
+        for part in [1, 2] {
+            if let Some(expected) = fixture.answer(part) {
+                let answer = solution.solve_any(part, parsed.as_ref());
+                assert_eq!(answer.as_ref(), Some(expected), "day {} part {}", day, part);
+            }
+        }
+    }
+
+    mod generated {
+        use super::check;
+
+        include!(concat!(env!("OUT_DIR"), "/fixture_tests.rs"));
+    }
+
+    #[test]
+    fn test_fixture_parse() {
+        let fixture = Fixture::parse(16, "letters", "part2: ##.\\n.##\n\n1 2\n3 4\n").unwrap();
+        assert_eq!(fixture.answer(1), None);
+        assert_eq!(
+            fixture.answer(2),
+            Some(&Answer::from(vec!["##.".to_string(), ".##".to_string()]))
+        );
+        assert_eq!(fixture.input, "1 2\n3 4\n");
+
+        let fixture = Fixture::parse(1, "crlf", "part1: 11\r\npart2: 31\r\n\r\n1 2\r\n").unwrap();
+        assert_eq!(fixture.answer(1), Some(&Answer::Unsigned(11)));
+        assert_eq!(fixture.answer(2), Some(&Answer::Unsigned(31)));
+        assert_eq!(fixture.input, "1 2\r\n");
+
+        let error = Fixture::parse(1, "bad", "part1: 11\npart3: 5\n\n1 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = Fixture::parse(1, "bad", "part1: 11\n").unwrap_err();
+        assert_eq!(error.expected, "a blank line before the input");
+    }
+
+    #[test]
+    fn test_fixtures_load() {
+        assert!(names(1).unwrap().contains(&"example".to_string()));
+
+        let fixture = Fixture::load(1, "example").unwrap();
+        assert_eq!(fixture.answer(1), Some(&Answer::Unsigned(11)));
+        assert!(matches!(
+            Fixture::load(1, "missing"),
+            Err(LoadError::Io(..))
+        ));
+        assert!(names(99).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use rand::{rngs::StdRng, RngExt, SeedableRng};
+
+use crate::solution;
+
+/// Generates a random input for `day`, in the same format as the real one
+/// and about `scale` times its size, or `None` if the day has no generator.
+/// The same `seed` always gives the same input.
+pub fn generate(day: u32, seed: u64, scale: f64) -> Option<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    solution::solution(day)?.generate_any(&mut rng, scale)
+}
+
+/// `base` items scaled by `scale`, but at least one.
+pub fn count(base: usize, scale: f64) -> usize {
+    ((base as f64 * scale).round() as usize).max(1)
+}
+
+/// The side of a square grid with `scale` times the area of one whose side
+/// is `base`, but at least two.
+pub fn side(base: usize, scale: f64) -> usize {
+    ((base as f64 * scale.sqrt()).round() as usize).max(2)
+}
+
+/// A grid of `width` by `height` characters, one row per line.
+pub fn char_grid(
+    width: usize,
+    height: usize,
+    mut cell: impl FnMut(usize, usize) -> char,
+) -> String {
+    let mut output = String::with_capacity((width + 1) * height);
+    for y in 0..height {
+        output.extend((0..width).map(|x| cell(x, y)));
+        output.push('\n');
+    }
+    output
+}
+
+/// A number with between `min_digits` and `max_digits` digits, each length
+/// equally likely, so short numbers are as common as long ones.
+pub fn number(rng: &mut StdRng, min_digits: u32, max_digits: u32) -> u64 {
+    let digits = rng.random_range(min_digits..=max_digits);
+    let low = if digits == 1 {
+        0
+    } else {
+        10u64.pow(digits - 1)
+    };
+    rng.random_range(low..10u64.pow(digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::solutions;
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for solution in solutions() {
+            for seed in 0..3 {
+                let input = generate(solution.day(), seed, 0.02).unwrap();
+                let parsed = solution
+                    .parse_any(&input)
+                    .unwrap_or_else(|e| panic!("day {} seed {}: {}", solution.day(), seed, e));
+                for part in [1, 2] {
+                    solution.solve_any(part, parsed.as_ref());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_is_seeded() {
+        assert_eq!(generate(9, 7, 0.1), generate(9, 7, 0.1));
+        assert_ne!(generate(9, 7, 0.1), generate(9, 8, 0.1));
+        assert!(generate(9, 7, 1.0).unwrap().len() > generate(9, 7, 0.1).unwrap().len());
+        assert_eq!(generate(26, 7, 1.0), None);
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use glam::IVec2;
+use nom::{
+    character::complete::line_ending,
+    error::{ErrorKind, ParseError},
+    multi::many1,
+    IResult, Parser,
+};
+
+use crate::{direction::Direction, parse::end_of_line};
+
+/// A rectangular grid of cells stored in row-major order and indexed by
+/// `IVec2` positions, where `x` is the column and `y` is the row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    size: IVec2,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(size: IVec2, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            size,
+            cells: vec![fill; (size.x * size.y) as usize],
+        }
+    }
+
+    /// Builds a grid from rows of cells, returning `None` if there are no
+    /// rows or the rows are not all the same non-zero length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first()?.len();
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Self {
+            size: IVec2::new(width as i32, rows.len() as i32),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.size
+    }
+
+    pub fn width(&self) -> i32 {
+        self.size.x
+    }
+
+    pub fn height(&self) -> i32 {
+        self.size.y
+    }
+
+    pub fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.size).all()
+    }
+
+    fn index_of(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.y * self.size.x + pos.x) as usize)
+    }
+
+    fn position_of(&self, index: usize) -> IVec2 {
+        IVec2::new(index as i32 % self.size.x, index as i32 / self.size.x)
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `pos`, returning the previous value, or `None`
+    /// if `pos` is out of bounds.
+    pub fn set(&mut self, pos: IVec2, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> {
+        let size = self.size;
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// All cells with their positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.position_of(i), cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // A grid with no columns has no cells, so no rows either.
+        self.cells.chunks(self.size.x.max(1) as usize)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.size.x as usize)
+            .map(move |x| self.cells.iter().skip(x).step_by(self.size.x as usize))
+    }
+
+    /// The in-bounds positions directly above, right of, below and left of
+    /// `pos`.
+    pub fn neighbours4(&self, pos: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .map(move |d| pos + IVec2::from(d))
+            .filter(|p| self.in_bounds(*p))
+    }
+
+    /// The in-bounds positions surrounding `pos`, including diagonals.
+    pub fn neighbours8(&self, pos: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        Direction::ALL
+            .into_iter()
+            .map(move |d| pos + IVec2::from(d))
+            .filter(|p| self.in_bounds(*p))
+    }
+
+    /// The position of the first cell in row-major order matching
+    /// `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<IVec2> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| self.position_of(i))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            size: self.size,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn map_with_position<U>(&self, f: impl Fn(IVec2, &T) -> U) -> Grid<U> {
+        Grid {
+            size: self.size,
+            cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+        }
+    }
+
+    /// Parses newline-separated rows of cells into a grid. The grid ends at
+    /// the first line that doesn't start with a cell, and the line ending
+    /// after the last row is not consumed.
+    ///
+    /// Once a row has started, a character that isn't a cell or a row of a
+    /// different width is a hard failure, so errors point at the bad cell.
+    pub fn parse<'a, E, P>(mut cell: P) -> impl FnMut(&'a str) -> IResult<&'a str, Self, E>
+    where
+        E: ParseError<&'a str>,
+        P: Parser<&'a str, T, E>,
+    {
+        move |input: &'a str| {
+            let mut rows: Vec<Vec<T>> = Vec::new();
+            let mut rest = input;
+
+            loop {
+                let row_start = if rows.is_empty() {
+                    rest
+                } else {
+                    match line_ending::<_, E>(rest) {
+                        Ok((row_start, _)) if cell.parse(row_start).is_ok() => row_start,
+                        _ => break,
+                    }
+                };
+
+                let (row_end, row) = many1(|i| cell.parse(i))(row_start)?;
+                if end_of_line::<E>(row_end).is_err() {
+                    return Err(match cell.parse(row_end) {
+                        Err(nom::Err::Error(e)) => nom::Err::Failure(e),
+                        Err(e) => e,
+                        Ok(_) => unreachable!("many1 stops at the first rejected cell"),
+                    });
+                }
+                if rows.first().is_some_and(|first| first.len() != row.len()) {
+                    return Err(nom::Err::Failure(E::from_error_kind(
+                        row_start,
+                        ErrorKind::Verify,
+                    )));
+                }
+
+                rows.push(row);
+                rest = row_end;
+            }
+
+            Ok((
+                rest,
+                Self::from_rows(rows).expect("rows are non-empty and equal"),
+            ))
+        }
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: IVec2) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "position {} out of bounds for grid of size {}",
+                pos, self.size
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: IVec2) -> &mut Self::Output {
+        let size = self.size;
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("position {} out of bounds for grid of size {}", pos, size))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            if y < self.size.y as usize - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use nom::character::complete::one_of;
+
+    use super::*;
+
+    fn parse_grid(input: &str) -> Grid<char> {
+        Grid::parse::<nom::error::Error<&str>, _>(one_of("abcdef"))(input)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_grid_parse_and_display() {
+        let grid = parse_grid("abc\ndef\n");
+        assert_eq!(grid.size(), IVec2::new(3, 2));
+        assert_eq!(grid.get(IVec2::new(2, 0)), Some(&'c'));
+        assert_eq!(grid.get(IVec2::new(0, 1)), Some(&'d'));
+        assert_eq!(grid.get(IVec2::new(3, 0)), None);
+        assert_eq!(grid.get(IVec2::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        assert!(Grid::parse::<nom::error::Error<&str>, _>(one_of("abcdef"))("abc\nde").is_err());
+    }
+
+    #[test]
+    fn test_grid_rows_and_columns() {
+        let grid = parse_grid("abc\ndef");
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect_vec(),
+            vec!["abc", "def"]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect_vec(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(grid.find(|&c| c == 'e'), Some(IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = parse_grid("abc\ndef");
+        assert_eq!(grid.neighbours4(IVec2::ZERO).count(), 2);
+        assert_eq!(grid.neighbours8(IVec2::ZERO).count(), 3);
+        assert_eq!(grid.neighbours4(IVec2::new(1, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(IVec2::new(1, 0)).count(), 5);
+    }
+}
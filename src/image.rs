@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::render::{Cell, Frame};
+
+const BACKGROUND: [u8; 3] = [16, 16, 24];
+const FOREGROUND: [u8; 3] = [200, 200, 200];
+
+/// The colour a cell is drawn in. Empty cells are background, uncoloured
+/// cells are a light grey, and highlighted cells are lightened.
+fn pixel(cell: &Cell) -> [u8; 3] {
+    let rgb = match (cell.ch, cell.colour) {
+        (_, Some(colour)) => colour.rgb(),
+        ('.' | ' ', None) => BACKGROUND,
+        (_, None) => FOREGROUND,
+    };
+    if cell.highlight {
+        rgb.map(|c| c + (255 - c) / 2)
+    } else {
+        rgb
+    }
+}
+
+/// An RGB image of a [`Frame`], with each cell drawn as a square.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Draws `frame` with each cell `scale` pixels across.
+    pub fn from_frame(frame: &Frame, scale: u32) -> Self {
+        let scale = scale.max(1);
+        let size = frame.size();
+        let (width, height) = (size.x as u32 * scale, size.y as u32 * scale);
+
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for row in frame.cells().rows() {
+            let row: Vec<[u8; 3]> = row
+                .iter()
+                .flat_map(|cell| std::iter::repeat_n(pixel(cell), scale as usize))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    /// Writes a binary PPM, which almost any image viewer can open.
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.bytes())
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.bytes())
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    /// Writes a PNG or PPM depending on `path`'s extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let writer = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.write_ppm(writer),
+            Some("png") => self.write_png(writer),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't save {} as an image", path.display()),
+            )),
+        }
+    }
+}
+
+/// Writes images as the frames of a looping animated GIF.
+pub struct GifWriter<W: Write> {
+    encoder: gif::Encoder<W>,
+    width: u32,
+    height: u32,
+    /// Hundredths of a second between frames.
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    pub fn new(writer: W, width: u32, height: u32, delay: Duration) -> io::Result<Self> {
+        let too_big = || io::Error::new(io::ErrorKind::InvalidInput, "image too big for a GIF");
+        let mut encoder = gif::Encoder::new(
+            writer,
+            width.try_into().map_err(|_| too_big())?,
+            height.try_into().map_err(|_| too_big())?,
+            &[],
+        )
+        .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            encoder,
+            width,
+            height,
+            delay: (delay.as_millis() / 10).clamp(1, u16::MAX as u128) as u16,
+        })
+    }
+
+    /// Adds `image`, which must be the same size as the GIF.
+    pub fn push(&mut self, image: &Image) -> io::Result<()> {
+        if (image.width, image.height) != (self.width, self.height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "every frame of a GIF must be the same size",
+            ));
+        }
+
+        // Frames rarely use more than a handful of colours, so index them
+        // exactly rather than quantizing.
+        let mut palette: HashMap<[u8; 3], u8> = HashMap::new();
+        let indexed: Option<Vec<u8>> = image
+            .pixels
+            .iter()
+            .map(|rgb| {
+                let next = palette.len();
+                match palette.get(rgb) {
+                    Some(&index) => Some(index),
+                    None if next < 256 => {
+                        palette.insert(*rgb, next as u8);
+                        Some(next as u8)
+                    }
+                    None => None,
+                }
+            })
+            .collect();
+
+        let mut frame = match indexed {
+            Some(buffer) => {
+                let mut colours = vec![0; palette.len() * 3];
+                for (rgb, index) in &palette {
+                    colours[*index as usize * 3..][..3].copy_from_slice(rgb);
+                }
+                gif::Frame {
+                    width: self.width as u16,
+                    height: self.height as u16,
+                    buffer: buffer.into(),
+                    palette: Some(colours),
+                    ..gif::Frame::default()
+                }
+            }
+            None => gif::Frame::from_rgb_speed(
+                self.width as u16,
+                self.height as u16,
+                &image.bytes(),
+                10,
+            ),
+        };
+        frame.delay = self.delay;
+
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::*;
+    use crate::{grid::Grid, render::Colour};
+
+    fn frame() -> Frame {
+        let mut cells = Grid::new(IVec2::new(2, 1), Cell::new('.'));
+        cells[IVec2::new(1, 0)] = Cell::new('#').with_colour(Colour::Rgb(10, 20, 30));
+        Frame::new(cells)
+    }
+
+    #[test]
+    fn test_image_ppm() {
+        let image = Image::from_frame(&frame(), 2);
+        assert_eq!((image.width(), image.height()), (4, 2));
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        let row = [BACKGROUND, BACKGROUND, [10, 20, 30], [10, 20, 30]].concat();
+        assert_eq!(ppm, [b"P6\n4 2\n255\n".as_slice(), &row, &row].concat());
+    }
+
+    #[test]
+    fn test_image_png_and_gif() {
+        let image = Image::from_frame(&frame(), 3);
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let mut gif = Vec::new();
+        {
+            let mut writer = GifWriter::new(
+                &mut gif,
+                image.width(),
+                image.height(),
+                Duration::from_millis(100),
+            )
+            .unwrap();
+            writer.push(&image).unwrap();
+            writer.push(&image).unwrap();
+            assert!(writer.push(&Image::from_frame(&frame(), 1)).is_err());
+        }
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
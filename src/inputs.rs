@@ -0,0 +1,277 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+use crate::{fixture, solution::YEAR, ROOT};
+
+/// The name of a day's real puzzle input.
+pub const REAL: &str = "real";
+
+/// Names starting with this select a fixture rather than a file under the
+/// input directory, so `example/small` is the fixture called `small`.
+pub const EXAMPLE_PREFIX: &str = "example/";
+
+/// Puzzle inputs by year, day and name. The real input for a day is kept as
+/// `<root>/<year>/dayN.txt`, and any others, such as a teammate's input or a
+/// generated stress input, as `<root>/<year>/dayN/<name>.txt`. The examples
+/// in [`fixture`] are available too, named `example/<name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inputs {
+    root: PathBuf,
+}
+
+/// The `input` directory under [`ROOT`].
+impl Default for Inputs {
+    fn default() -> Self {
+        Self::new(Path::new(ROOT).join("input"))
+    }
+}
+
+/// Why an input couldn't be read or written.
+#[derive(Debug)]
+pub enum InputError {
+    /// There's no input by that name. `available` lists the ones there are.
+    Missing {
+        year: u32,
+        day: u32,
+        name: String,
+        path: PathBuf,
+        available: Vec<String>,
+    },
+    Io(PathBuf, io::Error),
+    Fixture(fixture::LoadError),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing {
+                year,
+                day,
+                name,
+                path,
+                available,
+            } => {
+                write!(
+                    f,
+                    "day {} of {} has no input named `{}` (looked for {})",
+                    day,
+                    year,
+                    name,
+                    path.display()
+                )?;
+                if available.is_empty() {
+                    write!(f, ", and no inputs at all")
+                } else {
+                    write!(f, "; it has {}", available.join(", "))
+                }
+            }
+            InputError::Io(path, e) => write!(f, "failed to access {}: {}", path.display(), e),
+            InputError::Fixture(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn year_dir(&self, year: u32) -> PathBuf {
+        self.root.join(year.to_string())
+    }
+
+    /// The directory of the inputs for `day` of `year` other than the real
+    /// one.
+    pub fn day_dir(&self, year: u32, day: u32) -> PathBuf {
+        self.year_dir(year).join(format!("day{}", day))
+    }
+
+    /// Where the input called `name` for `day` of `year` is kept.
+    pub fn path(&self, year: u32, day: u32, name: &str) -> PathBuf {
+        if let Some(fixture) = name.strip_prefix(EXAMPLE_PREFIX) {
+            return fixture::Fixture::path(day, fixture);
+        }
+        if name == REAL {
+            return self.year_dir(year).join(format!("day{}.txt", day));
+        }
+        self.day_dir(year, day).join(format!("{}.txt", name))
+    }
+
+    /// The registry of known answers for `year`'s inputs. See
+    /// [`crate::answers`].
+    pub fn answers_path(&self, year: u32) -> PathBuf {
+        self.year_dir(year).join("answers.txt")
+    }
+
+    /// The names of every input for `day` of `year`: the real one first if
+    /// there is one, then the others, then the examples.
+    pub fn names(&self, year: u32, day: u32) -> Result<Vec<String>, InputError> {
+        let mut names = Vec::new();
+        if self.path(year, day, REAL).is_file() {
+            names.push(REAL.to_string());
+        }
+
+        let dir = self.day_dir(year, day);
+        match fs::read_dir(&dir) {
+            Ok(entries) => {
+                let mut others = Vec::new();
+                for entry in entries {
+                    let path = entry.map_err(|e| InputError::Io(dir.clone(), e))?.path();
+                    if path.extension().is_some_and(|e| e == "txt") {
+                        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                            others.push(name.to_string());
+                        }
+                    }
+                }
+                names.extend(others.into_iter().sorted());
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(InputError::Io(dir, e)),
+        }
+
+        // Fixtures belong to the solutions, so only the year they solve has
+        // any.
+        if year == YEAR {
+            let fixtures = fixture::names(day).map_err(InputError::Fixture)?;
+            names.extend(
+                fixtures
+                    .iter()
+                    .map(|name| format!("{}{}", EXAMPLE_PREFIX, name)),
+            );
+        }
+
+        Ok(names)
+    }
+
+    /// Reads the input called `name` for `day` of `year`. An example is just
+    /// its input, without the answers the fixture gives.
+    pub fn read(&self, year: u32, day: u32, name: &str) -> Result<String, InputError> {
+        let path = self.path(year, day, name);
+        let missing = || -> InputError {
+            InputError::Missing {
+                year,
+                day,
+                name: name.to_string(),
+                path: path.clone(),
+                available: self.names(year, day).unwrap_or_default(),
+            }
+        };
+
+        if let Some(fixture) = name.strip_prefix(EXAMPLE_PREFIX) {
+            if year != YEAR {
+                return Err(missing());
+            }
+            return match fixture::Fixture::load(day, fixture) {
+                Ok(fixture) => Ok(fixture.input),
+                Err(fixture::LoadError::Io(_, e)) if e.kind() == io::ErrorKind::NotFound => {
+                    Err(missing())
+                }
+                Err(e) => Err(InputError::Fixture(e)),
+            };
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(input),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(missing()),
+            Err(e) => Err(InputError::Io(path, e)),
+        }
+    }
+
+    /// Saves `input` as the input called `name` for `day` of `year`,
+    /// returning where it was written. Examples can't be written, since a
+    /// fixture also needs its answers.
+    pub fn write(
+        &self,
+        year: u32,
+        day: u32,
+        name: &str,
+        input: &str,
+    ) -> Result<PathBuf, InputError> {
+        let path = self.path(year, day, name);
+        if name.starts_with(EXAMPLE_PREFIX) {
+            return Err(InputError::Io(
+                path,
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "examples are fixtures, which are written by hand",
+                ),
+            ));
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| InputError::Io(dir.to_path_buf(), e))?;
+        }
+        fs::write(&path, input).map_err(|e| InputError::Io(path.clone(), e))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(test: &str) -> Inputs {
+        let root = std::env::temp_dir().join(format!("aoc-inputs-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Inputs::new(root)
+    }
+
+    #[test]
+    fn test_inputs_paths() {
+        let inputs = Inputs::default();
+        let root = Path::new(ROOT).join("input");
+        assert_eq!(inputs.path(2024, 3, REAL), root.join("2024/day3.txt"));
+        assert_eq!(
+            inputs.path(2023, 3, "alice"),
+            root.join("2023/day3/alice.txt")
+        );
+        assert_eq!(
+            inputs.path(2024, 3, "example/small"),
+            Path::new(fixture::DIR).join("day3/small.txt")
+        );
+        assert_eq!(inputs.answers_path(2024), root.join("2024/answers.txt"));
+    }
+
+    #[test]
+    fn test_inputs_read_and_write() {
+        let inputs = scratch("read-write");
+        inputs.write(2024, 1, REAL, "1 2\n").unwrap();
+        inputs.write(2024, 1, "stress", "3 4\n").unwrap();
+        inputs.write(2024, 1, "alice", "5 6\n").unwrap();
+
+        assert_eq!(inputs.read(2024, 1, "stress").unwrap(), "3 4\n");
+        assert_eq!(inputs.read(2024, 1, REAL).unwrap(), "1 2\n");
+        assert!(inputs
+            .read(2024, 1, "example")
+            .is_err_and(|e| matches!(e, InputError::Missing { .. })));
+        assert!(inputs.read(2024, 1, "example/example").is_ok());
+        assert!(inputs.write(2024, 1, "example/new", "").is_err());
+
+        let names = inputs.names(2024, 1).unwrap();
+        assert_eq!(names[..3], ["real", "alice", "stress"]);
+        assert!(names.contains(&"example/example".to_string()));
+        assert!(!inputs
+            .names(2023, 1)
+            .unwrap()
+            .contains(&"example/example".to_string()));
+
+        let error = inputs.read(2024, 1, "bob").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("day 1 of 2024 has no input named `bob`"));
+        assert!(error
+            .to_string()
+            .contains("; it has real, alice, stress, example/"));
+        let error = inputs.read(2023, 2, REAL).unwrap_err();
+        assert!(error.to_string().ends_with(", and no inputs at all"));
+
+        fs::remove_dir_all(&inputs.root).unwrap();
+    }
+}
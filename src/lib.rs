@@ -1,19 +1,40 @@
 use aoc_runner_derive::aoc_lib;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+pub mod animate;
+pub mod answer;
+pub mod answers;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod differential;
+pub mod direction;
+pub mod fixture;
+pub mod generate;
+pub mod grid;
+pub mod image;
+pub mod inputs;
+pub mod parse;
+pub mod render;
+pub mod runner;
+pub mod solution;
+
+pub use answer::Answer;
+pub use parse::ParseError;
+
+/// The crate's root directory. Inputs, fixtures and sources are all found
+/// from here, so `aoc` can be run from any directory.
+pub const ROOT: &str = env!("CARGO_MANIFEST_DIR");
 
 aoc_lib! { year = 2024 }
@@ -0,0 +1,354 @@
+use std::fmt::Display;
+
+use glam::IVec2;
+use nom::{
+    character::complete::{char, i32, line_ending, none_of, space1},
+    combinator::{map_res, recognize},
+    error::{ErrorKind, FromExternalError},
+    multi::{many1, separated_list1},
+    sequence::{pair, separated_pair},
+    Parser,
+};
+
+use crate::grid::Grid;
+
+const SNIPPET_LEN: usize = 20;
+
+/// What a parser was looking for when it failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Kind(ErrorKind),
+    Char(char),
+    Literal(&'static str),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Kind(kind) => write!(f, "{}", describe(*kind)),
+            Expected::Char(c) => write!(f, "{:?}", c),
+            Expected::Literal(literal) => write!(f, "{:?}", literal),
+        }
+    }
+}
+
+/// A nom error that keeps the character or literal the parser expected, so
+/// a [`ParseError`] can name it. Literals are only kept when matched with
+/// [`tag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Error<I> {
+    pub input: I,
+    pub expected: Expected,
+}
+
+impl<I> nom::error::ParseError<I> for Error<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self {
+            input,
+            expected: Expected::Kind(kind),
+        }
+    }
+
+    /// Keeps the innermost error, which says most precisely what was wrong.
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Self {
+            input,
+            expected: Expected::Char(c),
+        }
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for Error<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _: E) -> Self {
+        Self {
+            input,
+            expected: Expected::Kind(kind),
+        }
+    }
+}
+
+/// A nom result whose error is an [`Error`] unless another is given.
+pub type IResult<I, O, E = Error<I>> = nom::IResult<I, O, E>;
+
+/// Where and why a puzzle input failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the offending input.
+    pub line: usize,
+    /// 1-based column, in characters, of the offending input.
+    pub column: usize,
+    /// The input from the error position to the end of its line, truncated.
+    pub snippet: String,
+    /// A description of what the parser expected to find.
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Creates an error at the position of `remaining` within `input`, which
+    /// must be a suffix of `input`.
+    pub fn new(input: &str, remaining: &str, expected: impl Into<String>) -> Self {
+        let offset = input.len() - remaining.len();
+        let consumed = &input[..offset];
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            snippet: remaining
+                .split_inclusive('\n')
+                .next()
+                .unwrap_or("")
+                .chars()
+                .take(SNIPPET_LEN)
+                .collect(),
+            expected: expected.into(),
+        }
+    }
+
+    fn from_nom(input: &str, error: nom::Err<Error<&str>>) -> Self {
+        match error {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Self::new(input, e.input, e.expected.to_string())
+            }
+            nom::Err::Incomplete(_) => Self::new(input, "", "more input"),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        if self.snippet.is_empty() {
+            write!(f, " at end of input")
+        } else {
+            write!(f, " at {:?}", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Char => "a specific character",
+        ErrorKind::Tag => "a literal",
+        ErrorKind::Digit => "a number",
+        ErrorKind::OneOf | ErrorKind::NoneOf | ErrorKind::Satisfy => "an allowed character",
+        ErrorKind::CrLf => "a line ending",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::Eof => "end of input",
+        ErrorKind::MapOpt | ErrorKind::MapRes | ErrorKind::Verify => "a valid value",
+        ErrorKind::Many1 | ErrorKind::SeparatedList => "at least one item",
+        ErrorKind::ManyTill => "a terminator",
+        _ => kind.description(),
+    }
+    .to_lowercase()
+}
+
+/// Runs `parser` over the whole of `input`, allowing only trailing
+/// whitespace to remain, and converts any failure into a [`ParseError`].
+pub fn finish<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, T, Error<&'a str>>,
+) -> Result<T, ParseError> {
+    match parser.parse(input) {
+        Ok((rest, value)) if rest.trim().is_empty() => Ok(value),
+        Ok((rest, _)) => Err(ParseError::new(input, rest, "end of input")),
+        Err(e) => Err(ParseError::from_nom(input, e)),
+    }
+}
+
+/// Matches `literal`, naming it as what was expected if it isn't there.
+pub fn tag<'a>(literal: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input| {
+        nom::bytes::complete::tag(literal)(input).map_err(|e: nom::Err<Error<&str>>| {
+            e.map(|e| Error {
+                expected: Expected::Literal(literal),
+                ..e
+            })
+        })
+    }
+}
+
+/// Matches a line ending, or succeeds without consuming anything at the end
+/// of the input.
+pub fn end_of_line<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: nom::error::ParseError<&'a str>,
+{
+    if input.is_empty() {
+        Ok((input, input))
+    } else {
+        line_ending(input)
+    }
+}
+
+/// Matches one or more empty lines between two sections, including the line
+/// ending of the last line of the first section.
+pub fn blank_line<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: nom::error::ParseError<&'a str>,
+{
+    recognize(pair(line_ending, many1(line_ending)))(input)
+}
+
+/// Runs `item`, treating a failure as fatal unless the line it starts on is
+/// blank. An item in a list that fails partway through a line is malformed,
+/// and the list shouldn't just end before it, hiding why.
+fn committed<'a, O, E, F>(mut item: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    E: nom::error::ParseError<&'a str>,
+    F: Parser<&'a str, O, E>,
+{
+    move |input: &'a str| {
+        let blank = input
+            .lines()
+            .next()
+            .is_none_or(|line| line.trim().is_empty());
+        item.parse(input).map_err(|e| match e {
+            nom::Err::Error(e) if !blank => nom::Err::Failure(e),
+            e => e,
+        })
+    }
+}
+
+/// One `line` per line. The line ending after the last line is left for
+/// the caller, so `lines` can be followed by a [`blank_line`].
+pub fn lines<'a, O, E, F>(line: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    E: nom::error::ParseError<&'a str>,
+    F: Parser<&'a str, O, E>,
+{
+    separated_list1(line_ending, committed(line))
+}
+
+/// Blank-line-separated sections, each parsed by `section`.
+pub fn sections<'a, O, E, F>(section: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    E: nom::error::ParseError<&'a str>,
+    F: Parser<&'a str, O, E>,
+{
+    separated_list1(blank_line, committed(section))
+}
+
+/// Items on a single line separated by spaces or tabs.
+pub fn spaced<'a, O, E, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    E: nom::error::ParseError<&'a str>,
+    F: Parser<&'a str, O, E>,
+{
+    separated_list1(space1, item)
+}
+
+/// A single character converted through `T`'s `TryFrom<char>`.
+pub fn cell<'a, T, E>(input: &'a str) -> IResult<&'a str, T, E>
+where
+    T: TryFrom<char>,
+    E: nom::error::ParseError<&'a str> + FromExternalError<&'a str, T::Error>,
+{
+    map_res(none_of("\r\n"), T::try_from)(input)
+}
+
+/// A character grid with each cell converted through `T`'s `TryFrom<char>`.
+pub fn grid<'a, T, E>(input: &'a str) -> IResult<&'a str, Grid<T>, E>
+where
+    T: TryFrom<char>,
+    E: nom::error::ParseError<&'a str> + FromExternalError<&'a str, T::Error>,
+{
+    Grid::parse(cell)(input)
+}
+
+/// A signed `x,y` pair.
+pub fn ivec2<'a, E>(input: &'a str) -> IResult<&'a str, IVec2, E>
+where
+    E: nom::error::ParseError<&'a str>,
+{
+    separated_pair(i32, char(','), i32)
+        .map(|(x, y)| IVec2::new(x, y))
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::{newline, u32};
+
+    use super::*;
+
+    fn numbers(input: &str) -> Result<Vec<u32>, ParseError> {
+        finish(input, separated_list1(newline, u32))
+    }
+
+    #[test]
+    fn test_finish() {
+        assert_eq!(numbers("1\n2\n3\n"), Ok(vec![1, 2, 3]));
+
+        let error = numbers("1\n2\n3x4\n5").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(error.snippet, "x4\n");
+        assert_eq!(error.expected, "end of input");
+
+        let error = numbers("1\r\n2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 2: expected end of input at \"\\r\\n\""
+        );
+
+        let error = numbers("").unwrap_err();
+        assert_eq!(error.expected, "a number");
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a number at end of input"
+        );
+    }
+
+    #[test]
+    fn test_lines_and_sections() {
+        let input = "1 2\r\n3\t4\n\n5\r\n\r\n\r\n6 7\n";
+        assert_eq!(
+            finish(input, sections(lines(spaced(u32)))),
+            Ok(vec![
+                vec![vec![1, 2], vec![3, 4]],
+                vec![vec![5]],
+                vec![vec![6, 7]]
+            ])
+        );
+        assert_eq!(
+            finish("1,-2\n-3,4", lines(ivec2)),
+            Ok(vec![IVec2::new(1, -2), IVec2::new(-3, 4)])
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        #[derive(Debug, PartialEq)]
+        struct Wall(bool);
+
+        impl TryFrom<char> for Wall {
+            type Error = char;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                match c {
+                    '#' => Ok(Wall(true)),
+                    '.' => Ok(Wall(false)),
+                    _ => Err(c),
+                }
+            }
+        }
+
+        let walls: Grid<Wall> = finish("#.\r\n.#\r\n", grid).unwrap();
+        assert_eq!(walls.size(), IVec2::new(2, 2));
+        assert_eq!(walls.get(IVec2::new(1, 1)), Some(&Wall(true)));
+
+        let error = finish::<Grid<Wall>>("#.\n.x\n", grid).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
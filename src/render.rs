@@ -0,0 +1,220 @@
+use std::{fmt::Display, fs, io, path::Path};
+
+use glam::IVec2;
+
+use crate::grid::Grid;
+
+/// The colour of a cell, in a terminal or an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    /// Colours that are easy to tell apart, for things like file IDs.
+    pub const PALETTE: [Colour; 6] = [
+        Colour::Red,
+        Colour::Green,
+        Colour::Yellow,
+        Colour::Blue,
+        Colour::Magenta,
+        Colour::Cyan,
+    ];
+
+    /// A distinct colour for each `index`, for when there are more things
+    /// to tell apart than the palette has colours.
+    pub fn distinct(index: usize) -> Colour {
+        // Stepping the hue by the golden angle keeps nearby indices apart.
+        let hue = (index as f64 * 137.508) % 360.0;
+        let sector = hue / 60.0;
+        let x = 1.0 - (sector % 2.0 - 1.0).abs();
+        let (r, g, b) = match sector as u32 {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        let channel = |c: f64| (55.0 + c * 200.0) as u8;
+        Colour::Rgb(channel(r), channel(g), channel(b))
+    }
+
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Colour::Red => [220, 50, 47],
+            Colour::Green => [60, 200, 80],
+            Colour::Yellow => [235, 200, 40],
+            Colour::Blue => [50, 110, 230],
+            Colour::Magenta => [210, 60, 200],
+            Colour::Cyan => [40, 200, 210],
+            Colour::Grey => [110, 110, 110],
+            Colour::Rgb(r, g, b) => [r, g, b],
+        }
+    }
+
+    fn ansi_code(self) -> String {
+        match self {
+            Colour::Red => "31".to_string(),
+            Colour::Green => "32".to_string(),
+            Colour::Yellow => "33".to_string(),
+            Colour::Blue => "34".to_string(),
+            Colour::Magenta => "35".to_string(),
+            Colour::Cyan => "36".to_string(),
+            Colour::Grey => "90".to_string(),
+            Colour::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+}
+
+/// One character of a [`Frame`] and how to draw it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub ch: char,
+    pub colour: Option<Colour>,
+    /// Draws attention to the cell, e.g. the robot or an antinode.
+    pub highlight: bool,
+}
+
+impl Cell {
+    pub fn new(ch: char) -> Self {
+        Self {
+            ch,
+            colour: None,
+            highlight: false,
+        }
+    }
+
+    pub fn with_colour(self, colour: Colour) -> Self {
+        Self {
+            colour: Some(colour),
+            ..self
+        }
+    }
+
+    pub fn highlighted(self) -> Self {
+        Self {
+            highlight: true,
+            ..self
+        }
+    }
+
+    fn ansi_style(&self) -> Option<String> {
+        let mut codes = Vec::new();
+        if self.highlight {
+            codes.push("1;7".to_string());
+        }
+        if let Some(colour) = self.colour {
+            codes.push(colour.ansi_code());
+        }
+        (!codes.is_empty()).then(|| format!("\x1b[{}m", codes.join(";")))
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Cell::new(ch)
+    }
+}
+
+/// A rendered snapshot of a puzzle's state.
+///
+/// Its `Display` output is the plain text backend: the characters only, with
+/// rows separated by newlines and no trailing newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    pub fn new(cells: Grid<Cell>) -> Self {
+        Self { cells }
+    }
+
+    /// A frame of plain characters.
+    pub fn from_chars(chars: &Grid<char>) -> Self {
+        Self::new(chars.map(|&c| Cell::new(c)))
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.cells.size()
+    }
+
+    pub fn cells(&self) -> &Grid<Cell> {
+        &self.cells
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<&Cell> {
+        self.cells.get(pos)
+    }
+
+    /// The frame with ANSI escape codes for colours and highlights.
+    pub fn to_ansi(&self) -> String {
+        let mut output = String::new();
+
+        for (y, row) in self.cells.rows().enumerate() {
+            if y > 0 {
+                output.push('\n');
+            }
+            let mut current = None;
+            for cell in row {
+                let style = cell.ansi_style();
+                if style != current {
+                    if current.is_some() {
+                        output += "\x1b[0m";
+                    }
+                    if let Some(style) = &style {
+                        output += style;
+                    }
+                    current = style;
+                }
+                output.push(cell.ch);
+            }
+            if current.is_some() {
+                output += "\x1b[0m";
+            }
+        }
+
+        output
+    }
+
+    /// Writes the plain text frame to `path`, ending with a newline.
+    pub fn dump(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, format!("{}\n", self))
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cells.map(|cell| cell.ch))
+    }
+}
+
+/// Puzzle state that can be drawn as a [`Frame`].
+pub trait Render {
+    fn render(&self) -> Frame;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_backends() {
+        let mut cells = Grid::new(IVec2::new(3, 2), Cell::new('.'));
+        cells[IVec2::new(1, 0)] = Cell::new('@').highlighted();
+        cells[IVec2::new(0, 1)] = Cell::new('O').with_colour(Colour::Yellow);
+        cells[IVec2::new(1, 1)] = Cell::new('O').with_colour(Colour::Yellow);
+        let frame = Frame::new(cells);
+
+        assert_eq!(frame.to_string(), ".@.\nOO.");
+        assert_eq!(frame.to_ansi(), ".\x1b[1;7m@\x1b[0m.\n\x1b[33mOO\x1b[0m.");
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, debug_span, info_span};
+
+use crate::{
+    answer::Answer,
+    solution::{solutions, DynSolution},
+    ParseError,
+};
+
+/// The answer to one part of a day along with how long it took to get it.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    Parse(ParseError),
+    /// The solver ran but found no answer for the input.
+    NoAnswer,
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Parse(e) => write!(f, "failed to parse input: {}", e),
+            RunError::NoAnswer => write!(f, "no answer for this input"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ParseError> for RunError {
+    fn from(e: ParseError) -> Self {
+        RunError::Parse(e)
+    }
+}
+
+/// A single day and part of a registered [`Solution`](crate::solution::Solution).
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub day: u32,
+    pub part: u32,
+    solution: &'static dyn DynSolution,
+}
+
+impl Solver {
+    pub fn run(&self, input: &str) -> Result<Run, RunError> {
+        let _span = info_span!("run", day = self.day, part = self.part).entered();
+
+        let (parsed, parse_time) =
+            timed(|| debug_span!("parse").in_scope(|| self.solution.parse_any(input)));
+        let parsed = parsed?;
+        debug!(elapsed = ?parse_time, "parsed input");
+
+        let (answer, solve_time) = timed(|| {
+            debug_span!("solve").in_scope(|| self.solution.solve_any(self.part, parsed.as_ref()))
+        });
+        debug!(elapsed = ?solve_time, answer = ?answer, "solved");
+        Ok(Run {
+            answer: answer.ok_or(RunError::NoAnswer)?,
+            parse_time,
+            solve_time,
+        })
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Every solver, ordered by day and then part.
+pub fn solvers() -> impl Iterator<Item = Solver> {
+    solutions().iter().flat_map(|&solution| {
+        [1, 2].map(|part| Solver {
+            day: solution.day(),
+            part,
+            solution,
+        })
+    })
+}
+
+/// The solvers for `day`, optionally restricted to a single `part`.
+pub fn find(day: u32, part: Option<u32>) -> impl Iterator<Item = Solver> {
+    solvers().filter(move |s| s.day == day && part.is_none_or(|part| s.part == part))
+}
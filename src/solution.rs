@@ -0,0 +1,212 @@
+use std::any::Any;
+
+use rand::rngs::StdRng;
+
+use crate::{
+    animate::Simulation, answer::Answer, day1, day10, day11, day12, day13, day14, day15, day2,
+    day3, day4, day5, day6, day7, day8, day9, render::Frame, ParseError,
+};
+
+/// The year the registered solutions are for. Inputs and answers are kept
+/// per year, see [`crate::inputs`], so later years can share the layout.
+pub const YEAR: u32 = 2024;
+
+/// A function that solves one part of a day's parsed input.
+pub type Part<I> = fn(&I) -> Option<Answer>;
+
+/// One day's puzzle: how to parse its input and solve both parts.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// The answer to part 1, or `None` if the input has no answer.
+    fn part1(input: &Self::Input) -> Option<Answer>;
+
+    /// The answer to part 2, or `None` if the input has no answer.
+    fn part2(input: &Self::Input) -> Option<Answer>;
+
+    /// Draws the parsed input, for days whose state is visual.
+    fn render(_input: &Self::Input) -> Option<Frame> {
+        None
+    }
+
+    /// A step-by-step simulation of `part`, for days that have one.
+    fn simulate(_input: &Self::Input, _part: u32) -> Option<Box<dyn Simulation + '_>> {
+        None
+    }
+
+    /// A random input about `scale` times the size of the real one, for days
+    /// that can generate them. See [`crate::generate`].
+    fn generate(_rng: &mut StdRng, _scale: f64) -> Option<String> {
+        None
+    }
+
+    /// A straightforward implementation of `part` to check the optimized one
+    /// against, for days that keep one. See [`crate::differential`].
+    fn reference(_part: u32) -> Option<Part<Self::Input>> {
+        None
+    }
+}
+
+/// A [`Solution`] with its input type erased, so days can be looked up by
+/// number and stored together.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u32;
+
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+
+    /// Solves `part` for an input returned by [`DynSolution::parse_any`].
+    ///
+    /// # Panics
+    ///
+    /// If `part` isn't 1 or 2, or `input` was parsed by a different day.
+    fn solve_any(&self, part: u32, input: &dyn Any) -> Option<Answer>;
+
+    /// Draws an input returned by [`DynSolution::parse_any`], if the day
+    /// supports it.
+    ///
+    /// # Panics
+    ///
+    /// If `input` was parsed by a different day.
+    fn render_any(&self, input: &dyn Any) -> Option<Frame>;
+
+    /// A step-by-step simulation of `part` of an input returned by
+    /// [`DynSolution::parse_any`], if the day has one.
+    ///
+    /// # Panics
+    ///
+    /// If `input` was parsed by a different day.
+    fn simulate_any<'a>(&self, input: &'a dyn Any, part: u32) -> Option<Box<dyn Simulation + 'a>>;
+
+    /// A random input about `scale` times the size of the real one, if the
+    /// day can generate them.
+    fn generate_any(&self, rng: &mut StdRng, scale: f64) -> Option<String>;
+
+    /// Whether the day has a reference implementation of `part`.
+    fn has_reference(&self, part: u32) -> bool;
+
+    /// Solves `part` of an input returned by [`DynSolution::parse_any`] with
+    /// the day's reference implementation.
+    ///
+    /// # Panics
+    ///
+    /// If the day has no reference implementation of `part`, or `input` was
+    /// parsed by a different day.
+    fn solve_reference_any(&self, part: u32, input: &dyn Any) -> Option<Answer>;
+
+    /// Parses `input` and solves `part` of it.
+    fn answer(&self, part: u32, input: &str) -> Result<Option<Answer>, ParseError> {
+        let input = self.parse_any(input)?;
+        Ok(self.solve_any(part, input.as_ref()))
+    }
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn solve_any(&self, part: u32, input: &dyn Any) -> Option<Answer> {
+        let input = downcast::<S>(input);
+        match part {
+            1 => S::part1(input),
+            2 => S::part2(input),
+            _ => panic!("day {} has no part {}", S::DAY, part),
+        }
+    }
+
+    fn render_any(&self, input: &dyn Any) -> Option<Frame> {
+        S::render(downcast::<S>(input))
+    }
+
+    fn simulate_any<'a>(&self, input: &'a dyn Any, part: u32) -> Option<Box<dyn Simulation + 'a>> {
+        S::simulate(downcast::<S>(input), part)
+    }
+
+    fn generate_any(&self, rng: &mut StdRng, scale: f64) -> Option<String> {
+        S::generate(rng, scale)
+    }
+
+    fn has_reference(&self, part: u32) -> bool {
+        S::reference(part).is_some()
+    }
+
+    fn solve_reference_any(&self, part: u32, input: &dyn Any) -> Option<Answer> {
+        let reference = S::reference(part)
+            .unwrap_or_else(|| panic!("day {} has no reference for part {}", S::DAY, part));
+        reference(downcast::<S>(input))
+    }
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input
+where
+    S::Input: 'static,
+{
+    input
+        .downcast_ref::<S::Input>()
+        .unwrap_or_else(|| panic!("input was not parsed by day {}", S::DAY))
+}
+
+const SOLUTIONS: &[&dyn DynSolution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+];
+
+/// Every day's solution, in order.
+pub fn solutions() -> &'static [&'static dyn DynSolution] {
+    SOLUTIONS
+}
+
+/// The solution for `day`, if there is one.
+pub fn solution(day: u32) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::Fixture;
+
+    #[test]
+    fn test_solution_registry() {
+        for (i, solution) in solutions().iter().enumerate() {
+            assert_eq!(solution.day(), i as u32 + 1);
+        }
+
+        let day1 = solution(1).unwrap();
+        let example = Fixture::load(1, "example").unwrap();
+        assert_eq!(
+            day1.answer(2, &example.input),
+            Ok(example.answer(2).cloned())
+        );
+        assert_eq!(
+            day1::Day1::part1(&day1::Day1::parse("1 2\n").unwrap()),
+            Some(Answer::from(1u32))
+        );
+        assert!(solution(26).is_none());
+    }
+}